@@ -1,10 +1,11 @@
 //! `unclog` helps you build your changelog.
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use log::error;
 use simplelog::{ColorChoice, LevelFilter, TermLogger, TerminalMode};
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use unclog::{Changelog, Config, Error, PlatformId, Result};
+use unclog::{Changelog, Config, Error, LintSeverity, Platform, PlatformId, Result};
 
 const RELEASE_SUMMARY_TEMPLATE: &str = r#"<!--
     Add a summary for the release here.
@@ -70,6 +71,19 @@ enum Command {
         /// to infer the project URL.
         #[arg(short, long, default_value = "origin")]
         remote: String,
+
+        /// If automatically generating configuration, the platform on which
+        /// the project is hosted (i.e. "github", "gitlab", "gitea",
+        /// "bitbucket" or "azure-devops"). Only necessary if it cannot be
+        /// inferred from the remote's URL.
+        #[arg(name = "platform", long)]
+        maybe_platform: Option<Platform>,
+    },
+    /// Import an existing Markdown changelog, creating a folder for each of its
+    /// releases and sections, and a file for each of its entries.
+    Import {
+        /// The path to the existing changelog (e.g. "CHANGELOG.md").
+        changelog: PathBuf,
     },
     /// Automatically generate a configuration file, attempting to infer as many
     /// parameters as possible from your project's environment.
@@ -78,6 +92,13 @@ enum Command {
         #[arg(short, long, default_value = "origin")]
         remote: String,
 
+        /// The platform on which the project is hosted (i.e. "github",
+        /// "gitlab", "gitea", "bitbucket" or "azure-devops"). Only necessary if
+        /// it cannot be inferred from the remote's URL (e.g. for self-hosted
+        /// instances).
+        #[arg(name = "platform", long)]
+        maybe_platform: Option<Platform>,
+
         /// Overwrite any existing configuration file.
         #[arg(short, long)]
         force: bool,
@@ -99,21 +120,36 @@ enum Command {
 
         /// The ID of the change to add, which should include the number of the
         /// issue or PR to which the change applies (e.g. "820-change-api").
-        #[arg(short, long)]
-        id: String,
-
-        /// The issue number associated with this change, if any. Only relevant
-        /// if the `--message` flag is also provided. Only one of the
-        /// `--issue-no` or `--pull-request` flags can be specified at a time.
+        /// If not specified, it is inferred from the name of the current Git
+        /// branch (e.g. "820-change-api"), along with the issue, PR or ticket
+        /// to which the change applies.
+        #[arg(name = "id", short, long)]
+        maybe_id: Option<String>,
+
+        /// The number of an issue associated with this change, if any. Only
+        /// relevant if the `--message` flag is also provided. Can be specified
+        /// multiple times, and can be combined with `--pull-request`.
         #[arg(name = "issue_no", short = 'n', long = "issue-no")]
-        maybe_issue_no: Option<u32>,
+        issue_nos: Vec<u32>,
 
-        /// The number of the pull request associated with this change, if any.
-        /// Only relevant if the `--message` flag is also provided. Only one of
-        /// the `--issue-no` or `--pull-request` flags can be specified at a
-        /// time.
+        /// The number of a pull request associated with this change, if any.
+        /// Only relevant if the `--message` flag is also provided. Can be
+        /// specified multiple times, and can be combined with `--issue-no`.
         #[arg(name = "pull_request", short, long = "pull-request")]
-        maybe_pull_request: Option<u32>,
+        pull_requests: Vec<u32>,
+
+        /// The ID of a ticket in an external issue tracker associated with
+        /// this change (e.g. "PROJ-1234"), if any. Only relevant if the
+        /// `--message` flag is also provided. Requires a ticket URL template to
+        /// be configured. Can be specified multiple times.
+        #[arg(name = "ticket", short, long)]
+        tickets: Vec<String>,
+
+        /// The hash of a commit associated with this change, if any. Only
+        /// relevant if the `--message` flag is also provided. Recorded in the
+        /// entry's front matter. Can be specified multiple times.
+        #[arg(name = "commit", long)]
+        commits: Vec<String>,
 
         /// If specified, the change will automatically be generated from the
         /// default change template. Requires a project URL to be specified in
@@ -132,14 +168,65 @@ enum Command {
         #[arg(value_enum, short, long, default_value = "simple")]
         format: DuplicatesOutputFormat,
     },
-    /// Build the changelog from the input path and write the output to stdout.
+    /// Build the changelog from the input path and write the output to stdout
+    /// (or to a file, if specified).
     Build {
-        /// Render all changes, including released and unreleased ones.
-        #[arg(short, long)]
-        all: bool,
-        /// Only render unreleased changes.
-        #[arg(short, long)]
-        unreleased_only: bool,
+        #[command(flatten)]
+        opts: BuildOpts,
+
+        /// Write the output to this file instead of stdout. The file is
+        /// replaced atomically, so a failed build never leaves a partially
+        /// written file behind.
+        #[arg(name = "output", short, long)]
+        maybe_output: Option<PathBuf>,
+
+        /// Do not touch the output file if its content would not change (e.g.
+        /// to keep its modification time stable).
+        #[arg(long, requires = "output")]
+        skip_unchanged: bool,
+    },
+    /// Check that the given file is identical to the changelog that would be
+    /// built from the input path with the same options (e.g. to ensure in CI
+    /// that a committed CHANGELOG.md is up-to-date). Prints a diff and fails
+    /// if the two differ.
+    Check {
+        #[command(flatten)]
+        opts: BuildOpts,
+
+        /// The file to check against the built changelog.
+        #[arg(default_value = "CHANGELOG.md")]
+        target: PathBuf,
+    },
+    /// Check the changelog's entries for problems, like lines that are too
+    /// long or files that will be ignored because of their extension. Fails
+    /// if any problems with a severity of "error" are found.
+    Lint,
+    /// Format the changelog's entry files in place: rewrap them to the
+    /// configured wrap width, normalize their bullets to the configured bullet
+    /// style, normalize their line endings and trim trailing blank lines.
+    Fmt {
+        /// Do not modify any files, but fail if any entry files are not
+        /// formatted.
+        #[arg(long)]
+        check: bool,
+    },
+    /// Scan the Git history for pull requests merged since the last release
+    /// (based on their merge or squash commit subjects) that have no entries.
+    Scan {
+        /// The revision (e.g. a tag) after which to scan for merged pull
+        /// requests. Defaults to the latest release's ID.
+        #[arg(name = "since", long)]
+        maybe_since: Option<String>,
+
+        /// Create a placeholder unreleased entry for each pull request without
+        /// an entry.
+        #[arg(long)]
+        create: bool,
+
+        /// The section in which to create placeholder entries. Defaults to the
+        /// configured default section.
+        #[arg(name = "section", short, long, requires = "create")]
+        maybe_section: Option<String>,
     },
     /// Release any unreleased features.
     Release {
@@ -149,9 +236,65 @@ enum Command {
 
         /// The version string to use for the new release (e.g. "v0.1.0").
         version: String,
+
+        /// Also rebuild the changelog (as per `unclog build`) and write it to
+        /// this file (e.g. "CHANGELOG.md"). The file is included in the
+        /// release commit if `--commit` is specified.
+        #[arg(name = "output", short, long)]
+        maybe_output: Option<PathBuf>,
+
+        /// Stage the changes to the changelog and commit them, using the
+        /// configured commit message.
+        #[arg(long)]
+        commit: bool,
+
+        /// Create an annotated Git tag named after the release's version,
+        /// whose message is the release's rendered notes, pointing at the
+        /// release commit. Requires `--commit`.
+        #[arg(long, requires = "commit")]
+        tag: bool,
     },
 }
 
+#[derive(Args, Default)]
+struct BuildOpts {
+    /// Render all changes, including released and unreleased ones.
+    #[arg(short, long)]
+    all: bool,
+    /// Only render unreleased changes.
+    #[arg(short, long)]
+    unreleased_only: bool,
+    /// Only render the release with the given version (e.g. "v0.2.0").
+    #[arg(
+        name = "release",
+        short,
+        long,
+        conflicts_with_all = ["all", "unreleased_only", "from", "to"]
+    )]
+    maybe_release: Option<String>,
+    /// Only render releases from this version onwards (inclusive).
+    #[arg(name = "from", long, conflicts_with_all = ["all", "unreleased_only"])]
+    maybe_from: Option<String>,
+    /// Only render releases up to and including this version.
+    #[arg(name = "to", long, conflicts_with_all = ["all", "unreleased_only"])]
+    maybe_to: Option<String>,
+    /// The format in which to render the changelog.
+    #[arg(value_enum, short, long, default_value = "markdown")]
+    format: BuildOutputFormat,
+}
+
+#[derive(Debug, Clone, Default, Copy, ValueEnum)]
+enum BuildOutputFormat {
+    /// Render the changelog as Markdown.
+    #[default]
+    Markdown,
+    /// Write out the changelog's structure as JSON.
+    Json,
+    /// Render the changelog as Markdown in the Keep a Changelog format (see
+    /// https://keepachangelog.com).
+    KeepAChangelog,
+}
+
 #[derive(Debug, Clone, Default, Copy, ValueEnum)]
 enum DuplicatesOutputFormat {
     /// A simple table with no borders.
@@ -190,6 +333,7 @@ fn main() {
             maybe_epilogue_path,
             gen_config,
             remote,
+            maybe_platform,
         } => init_changelog(
             &config,
             &opt.path,
@@ -198,65 +342,89 @@ fn main() {
             maybe_epilogue_path,
             gen_config,
             &remote,
+            maybe_platform,
         ),
-        Command::GenerateConfig { remote, force } => {
-            Changelog::generate_config(&config_path, opt.path, remote, force)
-        }
+        Command::Import { changelog } => Changelog::import(&config, &opt.path, changelog),
+        Command::GenerateConfig {
+            remote,
+            maybe_platform,
+            force,
+        } => Changelog::generate_config(&config_path, opt.path, remote, maybe_platform, force),
         Command::Build {
-            all,
-            unreleased_only,
-        } => build_changelog(&config, &opt.path, all, unreleased_only),
+            opts,
+            maybe_output,
+            skip_unchanged,
+        } => build_changelog(&config, &opt.path, opts, maybe_output, skip_unchanged),
+        Command::Check { opts, target } => check_changelog(&config, &opt.path, opts, &target),
+        Command::Lint => lint_changelog(&config, &opt.path),
+        Command::Fmt { check } => format_changelog(&config, &opt.path, check),
         Command::Add {
             editor,
             maybe_component,
             section,
-            id,
-            maybe_issue_no,
-            maybe_pull_request,
+            maybe_id,
+            issue_nos,
+            pull_requests,
+            tickets,
+            commits,
             maybe_message,
-        } => match maybe_message {
-            Some(message) => match maybe_issue_no {
-                Some(issue_no) => match maybe_pull_request {
-                    Some(_) => Err(Error::EitherIssueNoOrPullRequest),
-                    None => Changelog::add_unreleased_entry_from_template(
-                        &config,
-                        &opt.path,
-                        &section,
-                        maybe_component,
-                        &id,
-                        PlatformId::Issue(issue_no),
-                        &message,
-                    ),
-                },
-                None => match maybe_pull_request {
-                    Some(pull_request) => Changelog::add_unreleased_entry_from_template(
-                        &config,
-                        &opt.path,
-                        &section,
-                        maybe_component,
-                        &id,
-                        PlatformId::PullRequest(pull_request),
-                        &message,
-                    ),
-                    None => Err(Error::MissingIssueNoOrPullRequest),
-                },
-            },
-            None => add_unreleased_entry_with_editor(
+        } => {
+            let mut platform_ids = issue_nos
+                .into_iter()
+                .map(PlatformId::Issue)
+                .chain(pull_requests.into_iter().map(PlatformId::PullRequest))
+                .chain(tickets.into_iter().map(PlatformId::Ticket))
+                .chain(commits.into_iter().map(PlatformId::Commit))
+                .collect::<Vec<PlatformId>>();
+            infer_entry_id(
                 &config,
-                &editor,
                 &opt.path,
-                &section,
-                maybe_component,
-                &id,
-            ),
-        },
+                maybe_id,
+                &mut platform_ids,
+                maybe_message.is_some(),
+            )
+            .and_then(|id| match maybe_message {
+                Some(message) => Changelog::add_unreleased_entry_from_template(
+                    &config,
+                    &opt.path,
+                    &section,
+                    maybe_component,
+                    &id,
+                    &platform_ids,
+                    &message,
+                ),
+                None => add_unreleased_entry_with_editor(
+                    &config,
+                    &editor,
+                    &opt.path,
+                    &section,
+                    maybe_component,
+                    &id,
+                ),
+            })
+        }
         Command::FindDuplicates {
             include_changelog_path,
             format,
         } => find_duplicates(&config, &opt.path, include_changelog_path, format),
-        Command::Release { editor, version } => {
-            prepare_release(&config, &editor, &opt.path, &version)
-        }
+        Command::Scan {
+            maybe_since,
+            create,
+            maybe_section,
+        } => scan_changelog(&config, &opt.path, maybe_since, create, maybe_section),
+        Command::Release {
+            editor,
+            version,
+            maybe_output,
+            commit,
+            tag,
+        } => prepare_release(&config, &editor, &opt.path, &version).and_then(|released| {
+            if released {
+                finalize_release(&config, &opt.path, &version, maybe_output, commit, tag)
+            } else {
+                Ok(())
+            }
+        }),
     };
     if let Err(e) = result {
         error!("Failed: {}", e);
@@ -264,6 +432,7 @@ fn main() {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn init_changelog(
     config: &Config,
     path: &Path,
@@ -272,32 +441,312 @@ fn init_changelog(
     maybe_epilogue_path: Option<PathBuf>,
     gen_config: bool,
     remote: &str,
+    maybe_platform: Option<Platform>,
 ) -> Result<()> {
     Changelog::init_dir(config, path, maybe_prologue_path, maybe_epilogue_path)?;
     if gen_config {
-        Changelog::generate_config(config_path, path, remote, true)
+        Changelog::generate_config(config_path, path, remote, maybe_platform, true)
     } else {
         Ok(())
     }
 }
 
-fn build_changelog(config: &Config, path: &Path, all: bool, unreleased_only: bool) -> Result<()> {
-    if all && unreleased_only {
+/// Which specific release(s) to build, if any.
+enum ReleaseSelection {
+    All,
+    Single(String),
+    Range(Option<String>, Option<String>),
+}
+
+impl BuildOpts {
+    fn release_selection(&self) -> ReleaseSelection {
+        match &self.maybe_release {
+            Some(release) => ReleaseSelection::Single(release.clone()),
+            None if self.maybe_from.is_some() || self.maybe_to.is_some() => {
+                ReleaseSelection::Range(self.maybe_from.clone(), self.maybe_to.clone())
+            }
+            None => ReleaseSelection::All,
+        }
+    }
+}
+
+fn build_changelog(
+    config: &Config,
+    path: &Path,
+    opts: BuildOpts,
+    maybe_output: Option<PathBuf>,
+    skip_unchanged: bool,
+) -> Result<()> {
+    // Ensure the output always ends with exactly one newline, regardless of
+    // the format.
+    let rendered = format!(
+        "{}\n",
+        render_changelog(config, path, opts)?.trim_end_matches('\n')
+    );
+    match maybe_output {
+        Some(output) => write_output(&output, &rendered, skip_unchanged).map(|_| ()),
+        None => {
+            print!("{rendered}");
+            Ok(())
+        }
+    }
+}
+
+// Writes the given content to the given file, unless `skip_unchanged` is set
+// and the file already has that content. Returns whether the file was written.
+fn write_output(output: &Path, content: &str, skip_unchanged: bool) -> Result<bool> {
+    if skip_unchanged && std::fs::read_to_string(output).is_ok_and(|existing| existing == content) {
+        log::info!("{} is unchanged - not writing", output.display());
+        return Ok(false);
+    }
+    write_atomically(output, content)?;
+    log::info!("Wrote changelog to {}", output.display());
+    Ok(true)
+}
+
+// Writes the given content to a temporary file in the same directory as the
+// target path, flushes it to disk and then renames it to the target path.
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let maybe_permissions = std::fs::metadata(path).ok().map(|m| m.permissions());
+    let mut builder = tempfile::Builder::new();
+    if maybe_permissions.is_none() {
+        use_new_file_permissions(&mut builder);
+    }
+    let mut tmpfile = builder
+        .tempfile_in(dir)
+        .map_err(|e| Error::Io(dir.to_path_buf(), e))?;
+    let tmpfile_path = tmpfile.path().to_path_buf();
+    tmpfile
+        .write_all(content.as_bytes())
+        .map_err(|e| Error::Io(tmpfile_path.clone(), e))?;
+    // Keep the permissions of the file we're replacing.
+    if let Some(permissions) = maybe_permissions {
+        tmpfile
+            .as_file()
+            .set_permissions(permissions)
+            .map_err(|e| Error::Io(tmpfile_path.clone(), e))?;
+    }
+    // Make sure the content is on disk before the rename, so that a crash
+    // can't leave an empty or truncated file behind.
+    tmpfile
+        .as_file()
+        .sync_all()
+        .map_err(|e| Error::Io(tmpfile_path, e))?;
+    tmpfile
+        .persist(path)
+        .map_err(|e| Error::Io(path.to_path_buf(), e.error))?;
+    sync_dir(dir)
+}
+
+// Temporary files are only accessible by their owner, so new files need to be
+// created with the usual permissions instead (which are still subject to the
+// umask).
+#[cfg(unix)]
+fn use_new_file_permissions(builder: &mut tempfile::Builder<'_, '_>) {
+    use std::os::unix::fs::PermissionsExt;
+    builder.permissions(std::fs::Permissions::from_mode(0o666));
+}
+
+#[cfg(not(unix))]
+fn use_new_file_permissions(_builder: &mut tempfile::Builder<'_, '_>) {}
+
+// Flushes the rename of a file in the given directory to disk.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> Result<()> {
+    std::fs::File::open(dir)
+        .and_then(|d| d.sync_all())
+        .map_err(|e| Error::Io(dir.to_path_buf(), e))
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> Result<()> {
+    Ok(())
+}
+
+fn check_changelog(config: &Config, path: &Path, opts: BuildOpts, target: &Path) -> Result<()> {
+    let rendered = render_changelog(config, path, opts)?;
+    let result = unclog::check_changelog_file(target, &rendered);
+    match &result {
+        Ok(()) => log::info!("{} is up-to-date", target.display()),
+        Err(Error::ChangelogOutOfDate(_, diff)) => print!("{diff}"),
+        Err(_) => (),
+    }
+    result
+}
+
+fn lint_changelog(config: &Config, path: &Path) -> Result<()> {
+    let issues = Changelog::lint(config, path)?;
+    for issue in &issues {
+        println!("{issue}");
+    }
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == LintSeverity::Error)
+        .count();
+    if errors > 0 {
+        return Err(Error::LintFailed(errors));
+    }
+    log::info!("Found {} warning(s) and no errors", issues.len());
+    Ok(())
+}
+
+fn format_changelog(config: &Config, path: &Path, check: bool) -> Result<()> {
+    let changelog = Changelog::read_from_dir(config, path)?;
+    let unformatted = changelog.format_entries(config, path)?;
+    if check {
+        for (entry_file, _) in &unformatted {
+            println!("{}", entry_file.display());
+        }
+        if !unformatted.is_empty() {
+            return Err(Error::EntriesNotFormatted(unformatted.len()));
+        }
+        log::info!("All entries are formatted");
+        return Ok(());
+    }
+    for (entry_file, content) in unformatted {
+        write_atomically(&entry_file, &content)?;
+        log::info!("Formatted {}", entry_file.display());
+    }
+    Ok(())
+}
+
+fn scan_changelog(
+    config: &Config,
+    path: &Path,
+    maybe_since: Option<String>,
+    create: bool,
+    maybe_section: Option<String>,
+) -> Result<()> {
+    let changelog = Changelog::read_from_dir(config, path)?;
+    let maybe_since = maybe_since.or_else(|| changelog.releases.first().map(|r| r.id.clone()));
+    let pull_requests =
+        changelog.find_pull_requests_without_entries(path, maybe_since.as_deref())?;
+    if pull_requests.is_empty() {
+        log::info!("All merged pull requests have entries");
+        return Ok(());
+    }
+    for pr in &pull_requests {
+        println!("#{}: {} ({})", pr.number, pr.title, &pr.commit[..7]);
+    }
+    if create {
+        let section = maybe_section.unwrap_or_else(|| config.scan.default_section.clone());
+        Changelog::add_pull_request_entries(config, path, &section, &pull_requests)?;
+    }
+    Ok(())
+}
+
+fn render_changelog(config: &Config, path: &Path, opts: BuildOpts) -> Result<String> {
+    if opts.all && opts.unreleased_only {
         return Err(Error::CommandLine(
             "cannot combine --all and --unreleased-only flags when building the changelog"
                 .to_string(),
         ));
     }
-    let changelog = Changelog::read_from_dir(config, path)?;
+    let releases = opts.release_selection();
+    let mut changelog = Changelog::read_from_dir(config, path)?;
+    read_entry_authors(&mut changelog, config, path);
     log::info!("Success!");
-    if unreleased_only {
-        println!("{}", changelog.render_unreleased(config)?);
-    } else if all {
-        println!("{}", changelog.render_all(config));
-    } else {
-        println!("{}", changelog.render_released(config));
+    match opts.format {
+        BuildOutputFormat::Markdown => match releases {
+            ReleaseSelection::Single(release) => changelog.render_release(config, release),
+            ReleaseSelection::Range(maybe_from, maybe_to) => {
+                changelog.render_release_range(config, maybe_from, maybe_to)
+            }
+            ReleaseSelection::All => {
+                if opts.unreleased_only {
+                    changelog.render_unreleased(config)
+                } else if opts.all {
+                    changelog.render_all(config)
+                } else {
+                    changelog.render_released(config)
+                }
+            }
+        },
+        BuildOutputFormat::Json => if let ReleaseSelection::Single(release) = releases {
+            let release = changelog
+                .releases_in_range(Some(&release), Some(&release))?
+                .into_iter()
+                .next()
+                .ok_or(Error::NoSuchRelease(release))?;
+            serde_json::to_string_pretty(release)
+        } else if let ReleaseSelection::Range(maybe_from, maybe_to) = releases {
+            let releases = changelog.releases_in_range(maybe_from, maybe_to)?;
+            if releases.is_empty() {
+                return Err(Error::NoReleasesInRange);
+            }
+            serde_json::to_string_pretty(&releases)
+        } else if opts.unreleased_only {
+            let unreleased = changelog
+                .maybe_unreleased
+                .filter(|u| !u.is_empty())
+                .ok_or(Error::NoUnreleasedEntries)?;
+            serde_json::to_string_pretty(&unreleased)
+        } else if opts.all {
+            serde_json::to_string_pretty(&changelog)
+        } else {
+            serde_json::to_string_pretty(&Changelog {
+                maybe_unreleased: None,
+                ..changelog
+            })
+        }
+        .map_err(Error::from),
+        BuildOutputFormat::KeepAChangelog => {
+            if opts.unreleased_only || !matches!(releases, ReleaseSelection::All) {
+                return Err(Error::CommandLine(
+                    "the Keep a Changelog format only supports building the whole changelog"
+                        .to_string(),
+                ));
+            }
+            if opts.all {
+                changelog.render_keep_a_changelog_all(config)
+            } else {
+                changelog.render_keep_a_changelog_released(config)
+            }
+        }
+    }
+}
+
+// Returns the given entry ID, or infers it from the current Git branch if not
+// given. If the ID is inferred and a message is given but no platform IDs, the
+// platform ID inferred from the branch (if any) is also added to the given
+// platform IDs.
+fn infer_entry_id(
+    config: &Config,
+    path: &Path,
+    maybe_id: Option<String>,
+    platform_ids: &mut Vec<PlatformId>,
+    has_message: bool,
+) -> Result<String> {
+    // Explicitly specified IDs take precedence over those inferred from the
+    // current branch.
+    if let Some(id) = maybe_id {
+        return Ok(id);
+    }
+    let maybe_inferred = match Changelog::entry_id_from_branch(config, path) {
+        Ok(maybe_inferred) => maybe_inferred,
+        Err(e) => {
+            log::debug!("Unable to infer entry ID from the current branch: {e}");
+            None
+        }
+    };
+    let (id, maybe_platform_id) = maybe_inferred.ok_or(Error::MissingEntryId)?;
+    log::info!("Inferred entry ID \"{id}\" from the current branch");
+    if has_message && platform_ids.is_empty() {
+        platform_ids.extend(maybe_platform_id);
+    }
+    Ok(id)
+}
+
+// Entry authors are only used to give credit, so failing to obtain them
+// shouldn't prevent rendering.
+fn read_entry_authors(changelog: &mut Changelog, config: &Config, path: &Path) {
+    if let Err(e) = changelog.read_entry_authors(config, path) {
+        log::warn!("Unable to obtain entry authors from Git history: {e}");
     }
-    Ok(())
 }
 
 fn add_unreleased_entry_with_editor(
@@ -308,6 +757,11 @@ fn add_unreleased_entry_with_editor(
     component: Option<String>,
     id: &str,
 ) -> Result<()> {
+    // Fail early, before the user has gone to the trouble of writing their
+    // entry.
+    if !config.change_set_sections.is_defined(section) {
+        return Err(Error::SectionNotDefined(section.to_owned()));
+    }
     let entry_path = Changelog::get_entry_path(
         config,
         path,
@@ -378,7 +832,8 @@ fn find_duplicates(
     Ok(())
 }
 
-fn prepare_release(config: &Config, editor: &Path, path: &Path, version: &str) -> Result<()> {
+// Returns whether the release was created.
+fn prepare_release(config: &Config, editor: &Path, path: &Path, version: &str) -> Result<bool> {
     // Add the summary to the unreleased folder, since we'll be moving it to
     // the new release folder
     let summary_path = path
@@ -402,8 +857,124 @@ fn prepare_release(config: &Config, editor: &Path, path: &Path, version: &str) -
         std::fs::read_to_string(&summary_path).map_err(|e| Error::Io(summary_path.clone(), e))?;
     if summary_content.is_empty() || summary_content == RELEASE_SUMMARY_TEMPLATE {
         log::info!("No changes to release summary - not creating a new release");
-        return Ok(());
+        return Ok(false);
+    }
+
+    Changelog::prepare_release_dir(config, path, version).map(|_| true)
+}
+
+fn finalize_release(
+    config: &Config,
+    path: &Path,
+    version: &str,
+    maybe_output: Option<PathBuf>,
+    commit: bool,
+    tag: bool,
+) -> Result<()> {
+    if let Some(output) = &maybe_output {
+        build_changelog(
+            config,
+            path,
+            BuildOpts::default(),
+            Some(output.clone()),
+            false,
+        )?;
+    }
+    if commit {
+        Changelog::commit_release(config, path, version, maybe_output.as_slice())?;
+    }
+    if tag {
+        let mut changelog = Changelog::read_from_dir(config, path)?;
+        read_entry_authors(&mut changelog, config, path);
+        changelog.tag_release(config, path, version)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{infer_entry_id, write_output};
+    use unclog::{Config, Error, PlatformId};
+
+    #[test]
+    fn entry_id_inference() {
+        let config = Config::default();
+        let tmpdir = tempfile::tempdir().unwrap();
+        let path = tmpdir.path();
+
+        // Outside of a Git repository.
+        let mut platform_ids = Vec::new();
+        assert!(matches!(
+            infer_entry_id(&config, path, None, &mut platform_ids, true),
+            Err(Error::MissingEntryId)
+        ));
+
+        let repo = git2::Repository::init(path).unwrap();
+        repo.set_head("refs/heads/1234-some-fix").unwrap();
+        assert_eq!(
+            infer_entry_id(&config, path, None, &mut platform_ids, true).unwrap(),
+            "1234-some-fix"
+        );
+        assert_eq!(platform_ids, vec![PlatformId::Issue(1234)]);
+
+        // Explicitly specified IDs aren't combined with the branch's issue.
+        let mut platform_ids = Vec::new();
+        assert_eq!(
+            infer_entry_id(
+                &config,
+                path,
+                Some("99-other".to_owned()),
+                &mut platform_ids,
+                true
+            )
+            .unwrap(),
+            "99-other"
+        );
+        assert!(platform_ids.is_empty());
+    }
+
+    #[test]
+    fn output_file_writing() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let output = tmpdir.path().join("CHANGELOG.md");
+
+        assert!(write_output(&output, "# Changelog\n", false).unwrap());
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "# Changelog\n");
+        // No temporary files are left behind.
+        assert_eq!(std::fs::read_dir(tmpdir.path()).unwrap().count(), 1);
+
+        // Unchanged content is only skipped if requested.
+        assert!(!write_output(&output, "# Changelog\n", true).unwrap());
+        assert!(write_output(&output, "# Changelog\n", false).unwrap());
+        assert!(write_output(&output, "# New changelog\n", true).unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            "# New changelog\n"
+        );
     }
 
-    Changelog::prepare_release_dir(config, path, version)
+    #[cfg(unix)]
+    #[test]
+    fn output_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let output = tmpdir.path().join("CHANGELOG.md");
+        std::fs::write(&output, "# Old changelog\n").unwrap();
+        std::fs::set_permissions(&output, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        write_output(&output, "# Changelog\n", false).unwrap();
+        let mode = std::fs::metadata(&output).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+
+        // New files get the same permissions as any other newly created file.
+        let reference = tmpdir.path().join("reference.md");
+        std::fs::write(&reference, "").unwrap();
+        let new_output = tmpdir.path().join("NEW.md");
+        write_output(&new_output, "# Changelog\n", false).unwrap();
+        assert_eq!(
+            std::fs::metadata(&new_output).unwrap().permissions().mode() & 0o777,
+            std::fs::metadata(&reference).unwrap().permissions().mode() & 0o777
+        );
+    }
 }
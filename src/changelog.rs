@@ -1,33 +1,44 @@
 //! Our model for a changelog.
 
+mod authors;
 mod change_set;
 mod change_set_section;
+mod check;
 mod component;
 mod component_section;
 pub mod config;
 mod entry;
 mod entry_path;
+mod formatting;
+mod import;
+mod keep_a_changelog;
+mod lint;
 mod parsing_utils;
 mod release;
+mod scan;
 
 pub use change_set::ChangeSet;
 pub use change_set_section::ChangeSetSection;
+pub use check::check_changelog_file;
 pub use component::Component;
 pub use component_section::ComponentSection;
-pub use entry::Entry;
+pub use entry::{Entry, EntryMetadata};
 pub use entry_path::{
     ChangeSetComponentPath, ChangeSetSectionPath, EntryChangeSetPath, EntryPath, EntryReleasePath,
 };
+pub use lint::LintIssue;
 pub use release::Release;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::changelog::config::SortReleasesBy;
+use crate::changelog::config::{SortReleasesBy, TemplatesConfig};
 use crate::changelog::parsing_utils::{extract_release_version, trim_newlines};
 use crate::fs_utils::{
     self, ensure_dir, path_to_str, read_and_filter_dir, read_to_string_opt, rm_gitkeep,
 };
-use crate::vcs::{from_git_repo, try_from, GenericProject};
-use crate::{Error, PlatformId, Result};
+use crate::git::{commit_paths, create_annotated_tag, current_branch};
+use crate::vcs::{from_git_repo, GenericProject, Platform};
+use crate::{Error, PlatformId, Result, Version};
 use config::Config;
 use log::{debug, info, warn};
 use std::collections::HashSet;
@@ -35,14 +46,15 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use self::change_set::ChangeSetIter;
+use self::entry::{ids_from_filename, with_front_matter};
 
-const DEFAULT_CHANGE_TEMPLATE: &str =
-    "{{{ bullet }}} {{{ message }}} ([\\#{{ change_id }}]({{{ change_url }}}))";
+const DEFAULT_CHANGE_TEMPLATE: &str = "{{{ bullet }}} {{{ message }}} ({{#each changes}}{{#unless @first}}, {{/unless}}[{{#if (or (eq kind \"issue\") (eq kind \"pull_request\"))}}\\#{{/if}}{{ id }}]({{{ url }}}){{/each}})";
 
 /// A log of changes for a specific project.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Changelog {
     /// Unreleased changes don't have version information associated with them.
+    #[serde(rename = "unreleased")]
     pub maybe_unreleased: Option<ChangeSet>,
     /// An ordered list of releases' changes.
     pub releases: Vec<Release>,
@@ -59,24 +71,24 @@ impl Changelog {
     pub fn is_empty(&self) -> bool {
         self.maybe_unreleased
             .as_ref()
-            .map_or(true, ChangeSet::is_empty)
+            .is_none_or(ChangeSet::is_empty)
             && self.releases.iter().all(|r| r.changes.is_empty())
-            && self.prologue.as_ref().map_or(true, String::is_empty)
-            && self.epilogue.as_ref().map_or(true, String::is_empty)
+            && self.prologue.as_ref().is_none_or(String::is_empty)
+            && self.epilogue.as_ref().is_none_or(String::is_empty)
     }
 
     /// Renders the full changelog to a string.
-    pub fn render_all(&self, config: &Config) -> String {
+    pub fn render_all(&self, config: &Config) -> Result<String> {
         self.render(config, true)
     }
 
     /// Renders all released versions' entries, excluding unreleased ones.
-    pub fn render_released(&self, config: &Config) -> String {
+    pub fn render_released(&self, config: &Config) -> Result<String> {
         self.render(config, false)
     }
 
-    fn render(&self, config: &Config, render_unreleased: bool) -> String {
-        let mut paragraphs = vec![config.heading.clone()];
+    fn render(&self, config: &Config, render_unreleased: bool) -> Result<String> {
+        let mut paragraphs = Vec::new();
         if self.is_empty() {
             paragraphs.push(config.empty_msg.clone());
         } else {
@@ -84,18 +96,101 @@ impl Changelog {
                 paragraphs.push(prologue.clone());
             }
             if render_unreleased {
-                if let Ok(unreleased_paragraphs) = self.unreleased_paragraphs(config) {
-                    paragraphs.extend(unreleased_paragraphs);
+                match self.unreleased_paragraphs(config) {
+                    Ok(unreleased_paragraphs) => paragraphs.extend(unreleased_paragraphs),
+                    Err(Error::NoUnreleasedEntries) => (),
+                    Err(e) => return Err(e),
                 }
             }
-            self.releases
-                .iter()
-                .for_each(|r| paragraphs.push(r.render(config)));
+            for release in &self.releases {
+                paragraphs
+                    .push(release.render_with_previous(config, self.previous_release(release))?);
+            }
             if let Some(epilogue) = self.epilogue.as_ref() {
                 paragraphs.push(epilogue.clone());
             }
         }
-        format!("{}\n", paragraphs.join("\n\n"))
+        let content = paragraphs.join("\n\n");
+        let rendered = config
+            .templates
+            .render(
+                TemplatesConfig::CHANGELOG,
+                self,
+                json!({
+                    "heading": config.heading,
+                    "content": content,
+                }),
+            )?
+            .unwrap_or_else(|| format!("{}\n\n{}", config.heading, content));
+        Ok(format!("{}\n", rendered))
+    }
+
+    /// Renders a single release, identified by its version (e.g. "v0.2.0" or
+    /// "0.2.0"), to a string.
+    pub fn render_release<S: AsRef<str>>(&self, config: &Config, version: S) -> Result<String> {
+        let version = version.as_ref();
+        let release = self
+            .find_release(parse_version(version)?)
+            .ok_or_else(|| Error::NoSuchRelease(version.to_owned()))?;
+        release.render_with_previous(config, self.previous_release(release))
+    }
+
+    /// Renders all releases whose versions fall within the given inclusive
+    /// range. If either end of the range is not specified, the range is
+    /// unbounded on that end.
+    pub fn render_release_range<F, T>(
+        &self,
+        config: &Config,
+        maybe_from: Option<F>,
+        maybe_to: Option<T>,
+    ) -> Result<String>
+    where
+        F: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let releases = self.releases_in_range(maybe_from, maybe_to)?;
+        if releases.is_empty() {
+            return Err(Error::NoReleasesInRange);
+        }
+        Ok(releases
+            .iter()
+            .map(|r| r.render_with_previous(config, self.previous_release(r)))
+            .collect::<Result<Vec<String>>>()?
+            .join("\n\n"))
+    }
+
+    /// Returns the release that comes after the given one in this changelog's
+    /// (sorted) list of releases, i.e. the one that preceded it.
+    pub fn previous_release(&self, release: &Release) -> Option<&Release> {
+        let i = self.releases.iter().position(|r| r.id == release.id)?;
+        self.releases.get(i + 1)
+    }
+
+    /// Attempts to find the release with the given version.
+    pub fn find_release(&self, version: Version) -> Option<&Release> {
+        self.releases.iter().find(|r| r.version == version)
+    }
+
+    /// Returns the releases whose versions fall within the given inclusive
+    /// range, in the order in which they appear in the changelog. If either
+    /// end of the range is not specified, the range is unbounded on that end.
+    pub fn releases_in_range<F, T>(
+        &self,
+        maybe_from: Option<F>,
+        maybe_to: Option<T>,
+    ) -> Result<Vec<&Release>>
+    where
+        F: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let maybe_from = maybe_from.map(|v| parse_version(v.as_ref())).transpose()?;
+        let maybe_to = maybe_to.map(|v| parse_version(v.as_ref())).transpose()?;
+        Ok(self
+            .releases
+            .iter()
+            .filter(|r| maybe_from.as_ref().is_none_or(|from| &r.version >= from))
+            .filter(|r| maybe_to.as_ref().is_none_or(|to| &r.version <= to))
+            .collect())
     }
 
     /// Renders just the unreleased changes to a string.
@@ -108,7 +203,7 @@ impl Changelog {
             if !unreleased.is_empty() {
                 return Ok(vec![
                     config.unreleased.heading.clone(),
-                    unreleased.render(config),
+                    unreleased.render(config)?,
                 ]);
             }
         }
@@ -177,7 +272,16 @@ impl Changelog {
 
     /// Attempts to generate a configuration file for the changelog in the given
     /// path, inferring as many parameters as possible from its environment.
-    pub fn generate_config<P, Q, S>(config_path: P, path: Q, remote: S, force: bool) -> Result<()>
+    ///
+    /// If a platform is specified, it is assumed (and written to the
+    /// configuration file) instead of being inferred from the remote's URL.
+    pub fn generate_config<P, Q, S>(
+        config_path: P,
+        path: Q,
+        remote: S,
+        maybe_platform: Option<Platform>,
+        force: bool,
+    ) -> Result<()>
     where
         P: AsRef<Path>,
         Q: AsRef<Path>,
@@ -205,7 +309,7 @@ impl Changelog {
         let git_folder = parent.join(".git");
 
         let maybe_git_project = if fs_utils::dir_exists(git_folder) {
-            Some(from_git_repo(parent, remote.as_ref())?)
+            Some(from_git_repo(parent, remote.as_ref(), maybe_platform)?)
         } else {
             warn!("Parent folder of changelog directory is not a Git repository. Cannot infer whether it is a GitHub project.");
             None
@@ -213,12 +317,17 @@ impl Changelog {
 
         let config = Config {
             maybe_project_url: maybe_git_project.map(|gp| gp.url()),
+            maybe_platform,
             ..Config::default()
         };
         config.write_to_file(config_path)
     }
 
     /// Attempt to read a full changelog from the given directory.
+    ///
+    /// The authors of its entries are not obtained from the project's Git
+    /// history, since that's only needed for rendering (see
+    /// [`Changelog::read_entry_authors`]).
     pub fn read_from_dir<P>(config: &Config, path: P) -> Result<Self>
     where
         P: AsRef<Path>,
@@ -297,9 +406,12 @@ impl Changelog {
         O: AsRef<str>,
     {
         let path = path.as_ref();
+        let section = section.as_ref();
+        if !config.change_set_sections.is_defined(section) {
+            return Err(Error::SectionNotDefined(section.to_string()));
+        }
         let unreleased_path = path.join(&config.unreleased.folder);
         ensure_dir(&unreleased_path)?;
-        let section = section.as_ref();
         let section_path = unreleased_path.join(section);
         ensure_dir(&section_path)?;
         let mut entry_dir = section_path;
@@ -325,7 +437,15 @@ impl Changelog {
     /// rendering them through the change template specified in the
     /// configuration file.
     ///
-    /// The change template is assumed to be in [Handlebars] format.
+    /// The change template is assumed to be in [Handlebars] format. At least
+    /// one platform ID must be specified.
+    ///
+    /// By default, the numbers of the issues and pull requests are prepended
+    /// to the entry's ID, while if an entry ID pattern is configured, the
+    /// first ticket is prepended instead. All other references (including
+    /// commits), as well as any pull requests (since numbers in filenames are
+    /// assumed to be issues), are recorded in the entry's front matter. Fails
+    /// if the resulting filename could not be loaded again.
     ///
     /// [Handlebars]: https://handlebarsjs.com/
     pub fn add_unreleased_entry_from_template(
@@ -334,7 +454,7 @@ impl Changelog {
         section: &str,
         component: Option<String>,
         id: &str,
-        platform_id: PlatformId,
+        platform_ids: &[PlatformId],
         message: &str,
     ) -> Result<()> {
         let rendered_change = Self::render_unreleased_entry_from_template(
@@ -343,22 +463,86 @@ impl Changelog {
             section,
             component.clone(),
             id,
-            platform_id,
+            platform_ids,
             message,
         )?;
+        let maybe_pattern = config.entry_ids.pattern()?;
+        // By default, only issue and pull request numbers can be loaded from
+        // entry filenames, whereas an entry ID pattern only matches a single
+        // ticket. Everything else goes into the entry's front matter.
+        let mut prefix_ids = Vec::new();
+        let mut metadata = EntryMetadata::default();
+        for platform_id in platform_ids {
+            match platform_id {
+                PlatformId::Issue(issue) if maybe_pattern.is_none() => {
+                    prefix_ids.push(issue.to_string())
+                }
+                PlatformId::Issue(issue) => metadata.issues.push(*issue),
+                PlatformId::PullRequest(pull_request) => {
+                    if maybe_pattern.is_none() {
+                        prefix_ids.push(pull_request.to_string());
+                    }
+                    // Numbers in filenames are assumed to be issues.
+                    metadata.pull_requests.push(*pull_request);
+                }
+                PlatformId::Ticket(ticket) if maybe_pattern.is_some() && prefix_ids.is_empty() => {
+                    prefix_ids.push(ticket.clone())
+                }
+                PlatformId::Ticket(ticket) => metadata.tickets.push(ticket.clone()),
+                // Commit hashes may start with digits, so they're never put
+                // into filenames.
+                PlatformId::Commit(commit) => metadata.commits.push(commit.clone()),
+            }
+        }
         let mut id = id.to_owned();
-        if !id.starts_with(&format!("{}-", platform_id.id())) {
-            id = format!("{}-{}", platform_id.id(), id);
-            debug!("Automatically prepending platform ID to change ID: {}", id);
+        if let Some(first) = prefix_ids.first() {
+            if !id.starts_with(&format!("{first}-")) {
+                id = format!("{}-{id}", prefix_ids.join("-"));
+                debug!(
+                    "Automatically prepending platform ID(s) to change ID: {}",
+                    id
+                );
+            }
         }
-        Self::add_unreleased_entry(config, path, section, component, &id, rendered_change)
+        // Make sure that the entry can be loaded again.
+        if let Err(e) =
+            ids_from_filename(&entry_id_to_filename(config, &id), maybe_pattern.as_ref())
+        {
+            return Err(match maybe_pattern {
+                Some(_) => e,
+                None => Error::EntryIdWithoutNumber(id),
+            });
+        }
+        let content = with_front_matter(&metadata, &rendered_change)?;
+        Self::add_unreleased_entry(config, path, section, component, &id, content)
+    }
+
+    /// Attempts to infer the ID of a new entry, and the platform ID of the
+    /// change to which it relates (if any), from the name of the currently
+    /// checked out branch of the Git repository containing the changelog at
+    /// the given path. Returns `None` if no branch is checked out or if the
+    /// branch name doesn't match the configured pattern.
+    pub fn entry_id_from_branch(
+        config: &Config,
+        path: &Path,
+    ) -> Result<Option<(String, Option<PlatformId>)>> {
+        let Some(branch) = current_branch(path)? else {
+            debug!("No branch is checked out");
+            return Ok(None);
+        };
+        let maybe_extracted = config.branches.pattern()?.extract(&branch);
+        if maybe_extracted.is_none() {
+            debug!("Branch \"{branch}\" does not match the configured pattern");
+        }
+        Ok(maybe_extracted)
     }
 
     /// Renders an unreleased changelog entry from the given parameters to a
     /// string, making use of the change template specified in the configuration
     /// file.
     ///
-    /// The change template is assumed to be in [Handlebars] format.
+    /// The change template is assumed to be in [Handlebars] format. At least
+    /// one platform ID must be specified.
     ///
     /// [Handlebars]: https://handlebarsjs.com/
     pub fn render_unreleased_entry_from_template(
@@ -367,15 +551,16 @@ impl Changelog {
         section: &str,
         component: Option<String>,
         id: &str,
-        platform_id: PlatformId,
+        platform_ids: &[PlatformId],
         message: &str,
     ) -> Result<String> {
-        let project_url = config
-            .maybe_project_url
-            .as_ref()
-            .ok_or(Error::MissingProjectUrl)?;
-        // We only support GitHub and GitLab projects at the moment
-        let git_project = try_from(project_url)?;
+        let platform_id = platform_ids
+            .first()
+            .ok_or(Error::MissingIssueNoOrPullRequest)?;
+        // The project is only needed to construct change URLs for which no
+        // templates are configured, so other platforms are fine otherwise.
+        let project = || config.project().unwrap_or(Err(Error::MissingProjectUrl));
+        let maybe_project = project().ok();
         let mut change_template_file = PathBuf::from(&config.change_template);
         if change_template_file.is_relative() {
             change_template_file = path.join(change_template_file);
@@ -391,19 +576,45 @@ impl Changelog {
         hb.register_template_string("change", change_template)
             .map_err(|e| Error::HandlebarsTemplateLoad(e.to_string()))?;
 
-        let (platform_id_field, platform_id_val) = match platform_id {
-            PlatformId::Issue(issue) => ("issue", issue),
-            PlatformId::PullRequest(pull_request) => ("pull_request", pull_request),
+        let platform_id_val = match platform_id {
+            PlatformId::Issue(issue) => json!(issue),
+            PlatformId::PullRequest(pull_request) => json!(pull_request),
+            PlatformId::Ticket(ticket) | PlatformId::Commit(ticket) => json!(ticket),
         };
+        // URL templates in the configuration take precedence over the
+        // project's own URLs.
+        let change_url = |platform_id: &PlatformId| {
+            config
+                .change_urls
+                .change_url(platform_id, config.maybe_project_url.as_ref())
+                .unwrap_or_else(|| match &maybe_project {
+                    Some(project) => project.change_url(platform_id),
+                    None => project().and_then(|project| project.change_url(platform_id)),
+                })
+        };
+        let changes = platform_ids
+            .iter()
+            .map(|platform_id| {
+                Ok(json!({
+                    "kind": platform_id.kind(),
+                    "id": platform_id.id(),
+                    "url": change_url(platform_id)?.to_string(),
+                }))
+            })
+            .collect::<Result<Vec<serde_json::Value>>>()?;
         let template_params = json!({
-            "project_url": git_project.to_string(),
+            "project_url": maybe_project
+                .as_ref()
+                .map(|project| project.to_string())
+                .or_else(|| config.maybe_project_url.as_ref().map(url::Url::to_string)),
             "section": section,
             "component": component,
             "id": id,
-            platform_id_field: platform_id_val,
+            platform_id.kind(): platform_id_val,
             "message": message,
-            "change_url": git_project.change_url(platform_id)?.to_string(),
+            "change_url": change_url(platform_id)?.to_string(),
             "change_id": platform_id.id(),
+            "changes": changes,
             "bullet": config.bullet_style.to_string(),
         });
         debug!(
@@ -486,6 +697,48 @@ impl Changelog {
         Self::init_empty_unreleased_dir(config, path)
     }
 
+    /// Commits the changes made to the changelog at the given path by
+    /// releasing the given version (see [`Changelog::prepare_release_dir`]),
+    /// along with changes to any other given paths (e.g. a regenerated
+    /// `CHANGELOG.md` file), using the configured commit message.
+    pub fn commit_release<P: AsRef<Path>, S: AsRef<str>>(
+        config: &Config,
+        path: P,
+        version: S,
+        other_paths: &[PathBuf],
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let mut paths = vec![
+            path.join(&config.unreleased.folder),
+            path.join(version.as_ref()),
+        ];
+        paths.extend_from_slice(other_paths);
+        let message = config.release.commit_message_for(version.as_ref());
+        commit_paths(&paths, &message).map(|_| ())
+    }
+
+    /// Creates an annotated Git tag for the release with the given version,
+    /// pointing at the current `HEAD` of the Git repository containing the
+    /// changelog at the given path. The tag is named after the release's
+    /// version, and its message is the release's rendered notes (for which
+    /// [`Changelog::read_entry_authors`] must be called first if crediting
+    /// authors is enabled).
+    pub fn tag_release<P: AsRef<Path>, S: AsRef<str>>(
+        &self,
+        config: &Config,
+        path: P,
+        version: S,
+    ) -> Result<()> {
+        let version = version.as_ref();
+        let release = self
+            .releases
+            .iter()
+            .find(|release| release.id == version)
+            .ok_or_else(|| Error::NoSuchRelease(version.to_owned()))?;
+        let notes = release.render_with_previous(config, self.previous_release(release))?;
+        create_annotated_tag(path.as_ref(), version, &notes).map(|_| ())
+    }
+
     fn init_empty_unreleased_dir(config: &Config, path: &Path) -> Result<()> {
         let unreleased_dir = path.join(&config.unreleased.folder);
         ensure_dir(&unreleased_dir)?;
@@ -633,6 +886,10 @@ impl<'a> Iterator for ReleasesIter<'a> {
     }
 }
 
+fn parse_version(s: &str) -> Result<Version> {
+    Ok(Version::parse(extract_release_version(s)?)?)
+}
+
 fn entry_id_to_filename<S: AsRef<str>>(config: &Config, id: S) -> String {
     format!("{}.{}", id.as_ref(), config.change_sets.entry_ext)
 }
@@ -0,0 +1,51 @@
+//! Obtaining the authors of changelog entries from the project's Git history.
+
+use crate::git::{file_authors, Author};
+use crate::{ChangeSet, Changelog, Config, Entry, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+impl Changelog {
+    /// Sets the author of each of this changelog's entries to the author of
+    /// the commit that added the entry's file, given the path from which the
+    /// changelog was loaded. This walks the project's whole Git history, so it
+    /// does nothing unless crediting entries or listing contributors is
+    /// enabled, and only needs to be called before rendering.
+    pub fn read_entry_authors(&mut self, config: &Config, path: &Path) -> Result<()> {
+        if !config.authors.is_enabled() {
+            return Ok(());
+        }
+        let authors = file_authors(path)?;
+        if let Some(unreleased) = &mut self.maybe_unreleased {
+            set_change_set_authors(&authors, Path::new(&config.unreleased.folder), unreleased);
+        }
+        for release in &mut self.releases {
+            set_change_set_authors(&authors, Path::new(&release.id), &mut release.changes);
+        }
+        Ok(())
+    }
+}
+
+fn set_change_set_authors(
+    authors: &HashMap<PathBuf, Author>,
+    path: &Path,
+    change_set: &mut ChangeSet,
+) {
+    for section in &mut change_set.sections {
+        let section_path = path.join(&section.id);
+        set_entry_authors(authors, &section_path, &mut section.entries);
+        for component_section in &mut section.component_sections {
+            set_entry_authors(
+                authors,
+                &section_path.join(&component_section.id),
+                &mut component_section.entries,
+            );
+        }
+    }
+}
+
+fn set_entry_authors(authors: &HashMap<PathBuf, Author>, path: &Path, entries: &mut [Entry]) {
+    for entry in entries {
+        entry.maybe_author = authors.get(&path.join(&entry.filename)).cloned();
+    }
+}
@@ -2,15 +2,17 @@ use crate::changelog::fs_utils::{read_and_filter_dir, read_to_string_opt};
 use crate::changelog::parsing_utils::trim_newlines;
 use crate::{ChangeSetSection, Config, EntryChangeSetPath, Error, Result};
 use log::debug;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use super::change_set_section::ChangeSetSectionIter;
 
 /// A set of changes, either associated with a release or not.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ChangeSet {
     /// An optional high-level summary of the set of changes.
+    #[serde(rename = "summary")]
     pub maybe_summary: Option<String>,
     /// The sections making up the change set.
     pub sections: Vec<ChangeSetSection>,
@@ -20,7 +22,7 @@ impl ChangeSet {
     /// Returns true if this change set has no summary and no entries
     /// associated with it.
     pub fn is_empty(&self) -> bool {
-        self.maybe_summary.as_ref().map_or(true, String::is_empty) && self.are_sections_empty()
+        self.maybe_summary.as_ref().is_none_or(String::is_empty) && self.are_sections_empty()
     }
 
     /// Returns whether or not all the sections are empty.
@@ -42,8 +44,16 @@ impl ChangeSet {
             .into_iter()
             .map(|path| ChangeSetSection::read_from_dir(config, path))
             .collect::<Result<Vec<ChangeSetSection>>>()?;
-        // Sort sections alphabetically
-        sections.sort_by(|a, b| a.title.cmp(&b.title));
+        // Sort sections by their configured order first, and then
+        // alphabetically
+        sections.sort_by_cached_key(|s| {
+            let maybe_order = config
+                .change_set_sections
+                .all
+                .get(&s.id)
+                .and_then(|c| c.maybe_order);
+            (maybe_order.is_none(), maybe_order, s.title.clone())
+        });
         Ok(Self {
             maybe_summary: summary,
             sections,
@@ -65,16 +75,15 @@ impl ChangeSet {
         Self::read_from_dir(config, path).map(Some)
     }
 
-    pub fn render(&self, config: &Config) -> String {
+    pub fn render(&self, config: &Config) -> Result<String> {
         let mut paragraphs = Vec::new();
         if let Some(summary) = self.maybe_summary.as_ref() {
             paragraphs.push(summary.clone());
         }
-        self.sections
-            .iter()
-            .filter(|s| !s.is_empty())
-            .for_each(|s| paragraphs.push(s.render(config)));
-        paragraphs.join("\n\n")
+        for section in self.sections.iter().filter(|s| !s.is_empty()) {
+            paragraphs.push(section.render(config)?);
+        }
+        Ok(paragraphs.join("\n\n"))
     }
 }
 
@@ -4,21 +4,29 @@ use crate::changelog::fs_utils::{entry_filter, path_to_str, read_and_filter_dir}
 use crate::{
     ChangeSetComponentPath, ChangeSetSectionPath, ComponentSection, Config, Entry, Error, Result,
 };
-use log::debug;
+use log::{debug, warn};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::ffi::OsStr;
+use std::ops::RangeInclusive;
 use std::path::Path;
 
 use super::component_section::ComponentSectionIter;
+use super::config::TemplatesConfig;
 
 /// A single section in a set of changes.
 ///
 /// For example, the "FEATURES" or "BREAKING CHANGES" section.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ChangeSetSection {
     /// Original ID of this change set section (the folder name).
     pub id: String,
     /// A short, descriptive title for this section (e.g. "BREAKING CHANGES").
     pub title: String,
+    /// An optional description of this section, rendered beneath its title.
+    #[serde(rename = "description")]
+    pub maybe_description: Option<String>,
     /// General entries in the change set section.
     pub entries: Vec<Entry>,
     /// Entries associated with a specific component/package/submodule.
@@ -43,7 +51,14 @@ impl ChangeSetSection {
             .and_then(OsStr::to_str)
             .ok_or_else(|| Error::CannotObtainName(path_to_str(path)))?
             .to_owned();
-        let title = change_set_section_title(&id);
+        if !config.change_set_sections.is_defined(&id) {
+            warn!("Change set section \"{id}\" is not defined in the configuration");
+        }
+        let maybe_section_config = config.change_set_sections.all.get(&id);
+        let title = maybe_section_config
+            .and_then(|s| s.maybe_title.clone())
+            .unwrap_or_else(|| change_set_section_title(&id));
+        let maybe_description = maybe_section_config.and_then(|s| s.maybe_description.clone());
         let component_section_dirs = read_and_filter_dir(path, package_section_filter)?;
         let mut component_sections = component_section_dirs
             .into_iter()
@@ -56,6 +71,7 @@ impl ChangeSetSection {
         Ok(Self {
             id,
             title,
+            maybe_description,
             entries,
             component_sections,
         })
@@ -63,7 +79,29 @@ impl ChangeSetSection {
 
     /// Render this change set section to a string using the given
     /// configuration.
-    pub fn render(&self, config: &Config) -> String {
+    pub fn render(&self, config: &Config) -> Result<String> {
+        let content = self.render_content(config)?;
+        Ok(config
+            .templates
+            .render(
+                TemplatesConfig::CHANGE_SET_SECTION,
+                self,
+                json!({
+                    "bullet": config.bullet_style.to_string(),
+                    "content": content,
+                }),
+            )?
+            .unwrap_or_else(|| match &self.maybe_description {
+                Some(description) => {
+                    format!("### {}\n\n{}\n\n{}", self.title, description, content)
+                }
+                None => format!("### {}\n\n{}", self.title, content),
+            }))
+    }
+
+    /// Render just the entries of this change set section (i.e. without its
+    /// title) to a string using the given configuration.
+    pub(crate) fn render_content(&self, config: &Config) -> Result<String> {
         let mut lines = Vec::new();
         // If we have no package sections
         if self.component_sections.is_empty() {
@@ -71,8 +109,8 @@ impl ChangeSetSection {
             lines.extend(
                 self.entries
                     .iter()
-                    .map(|e| e.to_string())
-                    .collect::<Vec<String>>(),
+                    .map(|e| e.render(config))
+                    .collect::<Result<Vec<String>>>()?,
             );
         } else {
             // If we do have package sections, however, we need to collect the
@@ -86,20 +124,21 @@ impl ChangeSetSection {
                 ));
                 // Now we indent all general entries.
                 lines.extend(indent_entries(
+                    config,
                     &self.entries,
                     config.components.entry_indent,
                     config.components.entry_indent + 2,
-                ));
+                )?);
             }
             // Component-specific sections are already indented
             lines.extend(
                 self.component_sections
                     .iter()
                     .map(|ps| ps.render(config))
-                    .collect::<Vec<String>>(),
+                    .collect::<Result<Vec<String>>>()?,
             );
         }
-        format!("### {}\n\n{}", self.title, lines.join("\n"))
+        Ok(lines.join("\n"))
     }
 }
 
@@ -220,36 +259,108 @@ fn change_set_section_title<S: AsRef<str>>(s: S) -> String {
     s.as_ref().to_owned().replace('-', " ").to_uppercase()
 }
 
-// Indents the given string according to `indent` and `overflow_indent`
-// assuming that the string contains one or more bulleted entries in Markdown.
-fn indent_bulleted_str(s: &str, indent: u8, overflow_indent: u8) -> Vec<String> {
-    s.split('\n')
-        .map(|line| {
-            let line_trimmed = line.trim();
-            let i = if line_trimmed.starts_with('*') || line_trimmed.starts_with('-') {
-                indent
-            } else {
-                overflow_indent
+// Indents the given Markdown string, assuming that it contains one or more
+// bulleted entries. Top-level list items start at `indent` spaces, and the rest
+// of their text is aligned with their content (i.e. at `overflow_indent` spaces
+// for bullets). Nested lists, code blocks, HTML blocks and block quotes are
+// shifted by `indent` spaces to preserve their structure.
+fn indent_markdown(s: &str, indent: u8, overflow_indent: u8) -> Vec<String> {
+    let (indent, overflow_indent) = (indent as usize, overflow_indent as usize);
+    let lines = s.split('\n').collect::<Vec<&str>>();
+    let line_starts = lines
+        .iter()
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len() + 1;
+            Some(start)
+        })
+        .collect::<Vec<usize>>();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+    let column_of = |offset: usize| offset - line_starts[line_of(offset)];
+
+    // The top-level list items, and the indentation of their content.
+    let mut items: Vec<(RangeInclusive<usize>, usize)> = Vec::new();
+    // Whether each line must be shifted as-is instead of being re-indented.
+    let mut shifted = vec![false; lines.len()];
+    let mut list_depth = 0;
+    // The index and column of the last top-level list item, until we know
+    // where its content starts.
+    let mut maybe_item_start: Option<(usize, usize)> = None;
+    for (event, range) in Parser::new(s).into_offset_iter() {
+        if let Some((item, item_column)) = maybe_item_start.take() {
+            // The content of the item starts where its first child does,
+            // however many spaces follow its marker.
+            if !matches!(event, Event::End(_)) {
+                let content_width = column_of(range.start).saturating_sub(item_column);
+                items[item].1 = indent + content_width;
+            }
+        }
+        let is_nested_block = match event {
+            Event::Start(Tag::List(_)) => {
+                list_depth += 1;
+                list_depth > 1
+            }
+            Event::End(TagEnd::List(_)) => {
+                list_depth -= 1;
+                false
+            }
+            Event::Start(Tag::Item) if list_depth == 1 => {
+                // Only used if the item turns out to have no content.
+                let item = lines[line_of(range.start)].trim_start();
+                let marker_width = item.find(' ').map_or(item.len(), |i| i + 1);
+                let lines = line_of(range.start)..=line_of(range.end - 1);
+                maybe_item_start = Some((items.len(), column_of(range.start)));
+                items.push((lines, indent + marker_width));
+                false
+            }
+            Event::Start(Tag::CodeBlock(_) | Tag::HtmlBlock | Tag::BlockQuote(_)) => true,
+            _ => false,
+        };
+        if is_nested_block && !range.is_empty() {
+            shifted[line_of(range.start)..=line_of(range.end - 1)].fill(true);
+        }
+    }
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if line.trim().is_empty() {
+                return String::new();
+            }
+            if shifted[i] {
+                return format!("{}{}", " ".repeat(indent), line.trim_end());
+            }
+            let i = match items.iter().find(|(lines, _)| lines.contains(&i)) {
+                Some((lines, _)) if *lines.start() == i => indent,
+                Some((_, content_indent)) => *content_indent,
+                None => overflow_indent,
             };
-            format!(
-                "{}{}",
-                (0..i).map(|_| " ").collect::<Vec<&str>>().join(""),
-                line_trimmed
-            )
+            format!("{}{}", " ".repeat(i), line.trim())
         })
         .collect::<Vec<String>>()
 }
 
-pub(crate) fn indent_entries(entries: &[Entry], indent: u8, overflow_indent: u8) -> Vec<String> {
-    entries
-        .iter()
-        .flat_map(|e| indent_bulleted_str(e.to_string().as_str(), indent, overflow_indent))
-        .collect::<Vec<String>>()
+pub(crate) fn indent_entries(
+    config: &Config,
+    entries: &[Entry],
+    indent: u8,
+    overflow_indent: u8,
+) -> Result<Vec<String>> {
+    let mut lines = Vec::new();
+    for entry in entries {
+        lines.extend(indent_markdown(
+            &entry.render(config)?,
+            indent,
+            overflow_indent,
+        ));
+    }
+    Ok(lines)
 }
 
 #[cfg(test)]
 mod test {
-    use super::{change_set_section_title, indent_bulleted_str};
+    use super::{change_set_section_title, indent_markdown};
 
     #[test]
     fn change_set_section_title_generation() {
@@ -289,10 +400,62 @@ mod test {
     which could overflow
   - It also has bulleted items which underflow"#,
             ),
+            (
+                r#"- An entry with a code block:
+  ```rust
+  fn main() {
+      println!("- not a bullet");
+  }
+  ```
+- And **bold**
+  **text** on a new line"#,
+                r#"  - An entry with a code block:
+    ```rust
+    fn main() {
+        println!("- not a bullet");
+    }
+    ```
+  - And **bold**
+    **text** on a new line"#,
+            ),
+            (
+                r#"- An entry with
+  - nested bullets
+    that overflow
+  1. and a numbered list
+
+  Another paragraph
+1. A numbered entry
+   that overflows"#,
+                r#"  - An entry with
+    - nested bullets
+      that overflow
+    1. and a numbered list
+
+    Another paragraph
+  1. A numbered entry
+     that overflows"#,
+            ),
+            (
+                r#"-   An entry with extra spaces
+    after its marker
+
+    And a second paragraph
+1.  A numbered entry
+
+    with a second paragraph"#,
+                r#"  -   An entry with extra spaces
+      after its marker
+
+      And a second paragraph
+  1.  A numbered entry
+
+      with a second paragraph"#,
+            ),
         ];
 
         for (s, expected) in cases {
-            let actual = indent_bulleted_str(s, 2, 4).join("\n");
+            let actual = indent_markdown(s, 2, 4).join("\n");
             assert_eq!(expected, actual);
         }
     }
@@ -0,0 +1,28 @@
+//! Checking whether an existing changelog file is up-to-date.
+
+use crate::{Error, Result};
+use std::path::Path;
+
+/// Checks that the file at the given path contains the given rendered
+/// changelog, ignoring any differences in the number of trailing newlines
+/// (e.g. to ensure that a committed `CHANGELOG.md` is up-to-date).
+///
+/// Fails with [`Error::ChangelogOutOfDate`] if the two differ, which includes
+/// a unified diff from the file's content to the rendered changelog.
+pub fn check_changelog_file<P: AsRef<Path>>(path: P, rendered: &str) -> Result<()> {
+    let path = path.as_ref();
+    let existing = std::fs::read_to_string(path).map_err(|e| Error::Io(path.to_path_buf(), e))?;
+    let (rendered, existing) = (
+        rendered.trim_end_matches('\n'),
+        existing.trim_end_matches('\n'),
+    );
+    if rendered == existing {
+        return Ok(());
+    }
+    let path_str = path.display().to_string();
+    let diff = similar::TextDiff::from_lines(existing, rendered)
+        .unified_diff()
+        .header(&path_str, "(built changelog)")
+        .to_string();
+    Err(Error::ChangelogOutOfDate(path_str, diff))
+}
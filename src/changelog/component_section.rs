@@ -3,12 +3,16 @@ use crate::changelog::entry::read_entries_sorted;
 use crate::changelog::fs_utils::{entry_filter, path_to_str, read_and_filter_dir};
 use crate::{Config, Entry, Error, Result};
 use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use super::config::TemplatesConfig;
+
 /// A section of entries related to a specific component/submodule/package.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ComponentSection {
     /// The ID of the component.
     pub id: String,
@@ -16,6 +20,7 @@ pub struct ComponentSection {
     pub name: String,
     /// The path to the component, from the root of the project, if any.
     /// Pre-computed and ready to render.
+    #[serde(rename = "path")]
     pub maybe_path: Option<String>,
     /// The entries associated with the component.
     pub entries: Vec<Entry>,
@@ -64,20 +69,32 @@ impl ComponentSection {
         })
     }
 
-    pub fn render(&self, config: &Config) -> String {
-        let entries_lines = indent_entries(
+    /// Render this component section to a string using the given
+    /// configuration.
+    pub fn render(&self, config: &Config) -> Result<String> {
+        let content = indent_entries(
+            config,
             &self.entries,
             config.components.entry_indent,
             config.components.entry_indent + 2,
-        );
+        )?
+        .join("\n");
+        if let Some(rendered) = config.templates.render(
+            TemplatesConfig::COMPONENT_SECTION,
+            self,
+            json!({
+                "bullet": config.bullet_style.to_string(),
+                "content": content,
+            }),
+        )? {
+            return Ok(rendered);
+        }
         let name = match &self.maybe_path {
             // Render as a Markdown hyperlink
             Some(path) => format!("[{}]({})", self.name, path),
             None => self.name.clone(),
         };
-        let mut lines = vec![format!("{} {}", config.bullet_style, name)];
-        lines.extend(entries_lines);
-        lines.join("\n")
+        Ok(format!("{} {}\n{}", config.bullet_style, name, content))
     }
 }
 
@@ -125,7 +142,7 @@ pub(crate) fn package_section_filter(entry: fs::DirEntry) -> Option<Result<PathB
 #[cfg(test)]
 mod test {
     use super::{ComponentSection, Config};
-    use crate::Entry;
+    use crate::{Entry, PlatformId};
 
     const RENDERED_WITH_PATH: &str = r#"- [Some project](./some-project/)
   - Issue 1
@@ -145,7 +162,7 @@ mod test {
             maybe_path: Some("./some-project/".to_owned()),
             entries: test_entries(),
         };
-        assert_eq!(RENDERED_WITH_PATH, ps.render(&Config::default()));
+        assert_eq!(RENDERED_WITH_PATH, ps.render(&Config::default()).unwrap());
     }
 
     #[test]
@@ -156,7 +173,10 @@ mod test {
             maybe_path: None,
             entries: test_entries(),
         };
-        assert_eq!(RENDERED_WITHOUT_PATH, ps.render(&Config::default()));
+        assert_eq!(
+            RENDERED_WITHOUT_PATH,
+            ps.render(&Config::default()).unwrap()
+        );
     }
 
     fn test_entries() -> Vec<Entry> {
@@ -165,16 +185,25 @@ mod test {
                 filename: "1-issue.md".to_string(),
                 id: 1,
                 details: "- Issue 1".to_string(),
+                metadata: Default::default(),
+                platform_ids: vec![PlatformId::Issue(1)],
+                maybe_author: None,
             },
             Entry {
                 filename: "2-issue.md".to_string(),
                 id: 2,
                 details: "- Issue 2".to_string(),
+                metadata: Default::default(),
+                platform_ids: vec![PlatformId::Issue(2)],
+                maybe_author: None,
             },
             Entry {
                 filename: "3-issue.md".to_string(),
                 id: 3,
                 details: "- Issue 3".to_string(),
+                metadata: Default::default(),
+                platform_ids: vec![PlatformId::Issue(3)],
+                maybe_author: None,
             },
         ]
     }
@@ -1,13 +1,17 @@
 //! Configuration-related types.
 
 use super::fs_utils::{path_to_str, read_to_string_opt};
-use crate::{Component, Error, Result};
+use crate::changelog::entry::EntryIdPattern;
+use crate::git::{Author, BranchPattern};
+use crate::vcs::{try_from_platform, Project};
+use crate::{Component, Error, Platform, PlatformId, Result};
 use log::{debug, info};
 use serde::{de::Error as _, Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::OnceLock;
 use url::Url;
 
 /// Configuration options relating to the generation of a changelog.
@@ -22,6 +26,11 @@ pub struct Config {
         skip_serializing_if = "is_default"
     )]
     pub maybe_project_url: Option<Url>,
+    /// The platform on which the project is hosted. If not specified, it is
+    /// inferred from the host of the project URL, which is not possible for
+    /// some self-hosted instances.
+    #[serde(default, rename = "platform", skip_serializing_if = "is_default")]
+    pub maybe_platform: Option<Platform>,
     /// The path to a file containing the change template to use when
     /// automatically adding new changelog entries. Relative to the `.changelog`
     /// folder.
@@ -79,6 +88,9 @@ pub struct Config {
     /// summaries to establish release dates.
     #[serde(default, skip_serializing_if = "is_default")]
     pub release_date_formats: ReleaseDateFormats,
+    /// Where to obtain release dates from.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub release_date_source: ReleaseDateSource,
 
     /// Configuration relating to unreleased changelog entries.
     #[serde(default, skip_serializing_if = "is_default")]
@@ -92,12 +104,42 @@ pub struct Config {
     /// Configuration relating to components/submodules.
     #[serde(default, skip_serializing_if = "is_default")]
     pub components: ComponentsConfig,
+    /// Configuration relating to the IDs at the start of entry filenames.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub entry_ids: EntryIdsConfig,
+    /// URL templates for references to changes.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub change_urls: ChangeUrlsConfig,
+    /// Configuration relating to inferring entry IDs from Git branch names.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub branches: BranchesConfig,
+    /// Templates to override the default rendering of the changelog.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub templates: TemplatesConfig,
+    /// Configuration relating to rendering the changelog in the [Keep a
+    /// Changelog](https://keepachangelog.com) format.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub keep_a_changelog: KeepAChangelogConfig,
+    /// Configuration relating to linting of entries.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub lint: LintConfig,
+    /// Configuration relating to scanning the project's Git history for
+    /// changes without entries.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub scan: ScanConfig,
+    /// Configuration relating to committing and tagging releases.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub release: ReleaseConfig,
+    /// Configuration relating to crediting the authors of entries.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub authors: AuthorsConfig,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             maybe_project_url: None,
+            maybe_platform: None,
             change_template: Self::default_change_template(),
             wrap: Self::default_wrap(),
             heading: Self::default_heading(),
@@ -108,14 +150,32 @@ impl Default for Config {
             unreleased: Default::default(),
             sort_releases_by: Default::default(),
             release_date_formats: Default::default(),
+            release_date_source: Default::default(),
             change_sets: Default::default(),
             change_set_sections: Default::default(),
             components: Default::default(),
+            entry_ids: Default::default(),
+            change_urls: Default::default(),
+            branches: Default::default(),
+            templates: Default::default(),
+            keep_a_changelog: Default::default(),
+            lint: Default::default(),
+            scan: Default::default(),
+            release: Default::default(),
+            authors: Default::default(),
         }
     }
 }
 
 impl Config {
+    /// Attempts to obtain the project from the configured project URL and
+    /// platform (if any). Returns `None` if no project URL is configured.
+    pub fn project(&self) -> Option<Result<Project>> {
+        self.maybe_project_url
+            .as_ref()
+            .map(|url| try_from_platform(url, self.maybe_platform))
+    }
+
     /// Attempt to read the configuration from the given file.
     ///
     /// If the given file does not exist, this method does not fail: it returns
@@ -131,7 +191,13 @@ impl Config {
         let maybe_content = read_to_string_opt(path)?;
         match maybe_content {
             Some(content) => {
-                toml::from_str::<Self>(&content).map_err(|e| Error::TomlParse(path_to_str(path), e))
+                let config = toml::from_str::<Self>(&content)
+                    .map_err(|e| Error::TomlParse(path_to_str(path), e))?;
+                // Catch any template and pattern errors early.
+                let _ = config.templates.registry()?;
+                let _ = config.entry_ids.pattern()?;
+                let _ = config.branches.pattern()?;
+                Ok(config)
             }
             None => {
                 info!("No changelog configuration file. Assuming defaults.");
@@ -203,11 +269,12 @@ impl Config {
 }
 
 /// The various styles of bullets available in Markdown.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub enum BulletStyle {
     /// `*`
     Asterisk,
     /// `-`
+    #[default]
     Dash,
 }
 
@@ -232,12 +299,6 @@ impl FromStr for BulletStyle {
     }
 }
 
-impl Default for BulletStyle {
-    fn default() -> Self {
-        Self::Dash
-    }
-}
-
 impl Serialize for BulletStyle {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
@@ -294,6 +355,20 @@ impl Default for ReleaseDateFormats {
     }
 }
 
+/// Where to obtain release dates from.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReleaseDateSource {
+    /// Parse the first line of each release's summary according to the
+    /// configured release date formats.
+    #[default]
+    Summary,
+    /// Use the date of the Git tag named after each release's ID (i.e. the
+    /// tagger's date for annotated tags, or otherwise the date of the tagged
+    /// commit).
+    GitTag,
+}
+
 /// Configuration relating to unreleased changelog entries.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct UnreleasedConfig {
@@ -354,6 +429,40 @@ pub struct ChangeSetSectionsConfig {
     /// Sort entries in change set sections by a specific property.
     #[serde(default, skip_serializing_if = "is_default")]
     pub sort_entries_by: SortEntriesBy,
+    /// All of the change set sections that may be used, keyed by their IDs
+    /// (folder names). If empty, any section ID is allowed.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub all: HashMap<String, ChangeSetSectionConfig>,
+}
+
+impl ChangeSetSectionsConfig {
+    /// Returns whether the section with the given ID may be used. If no
+    /// sections have been explicitly defined, all sections may be used.
+    pub fn is_defined(&self, id: &str) -> bool {
+        self.all.is_empty() || self.all.contains_key(id)
+    }
+}
+
+/// Configuration for a single change set section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChangeSetSectionConfig {
+    /// The title to display for this section. If not specified, the title is
+    /// derived from the section's ID (e.g. "breaking-changes" becomes
+    /// "BREAKING CHANGES").
+    #[serde(default, rename = "title", skip_serializing_if = "Option::is_none")]
+    pub maybe_title: Option<String>,
+    /// The order in which this section must be rendered relative to other
+    /// sections (lowest first). Sections without an order are rendered after
+    /// those with an order, sorted alphabetically by title.
+    #[serde(default, rename = "order", skip_serializing_if = "Option::is_none")]
+    pub maybe_order: Option<u16>,
+    /// An optional description to render beneath the section's title.
+    #[serde(
+        default,
+        rename = "description",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub maybe_description: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -418,3 +527,576 @@ pub enum SortEntriesBy {
     #[serde(rename = "entry-text")]
     EntryText,
 }
+
+/// [Handlebars] templates that override the default rendering of the various
+/// parts of the changelog. Any template that is not specified falls back to
+/// the default, built-in rendering.
+///
+/// Each template is given the data for its part of the changelog, as well as
+/// a `content` field containing the already-rendered content nested beneath
+/// it (e.g. a release's template gets its rendered change set). Output is not
+/// HTML-escaped.
+///
+/// [Handlebars]: https://handlebarsjs.com/
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplatesConfig {
+    #[serde(default, rename = "changelog", skip_serializing_if = "Option::is_none")]
+    maybe_changelog: Option<String>,
+    #[serde(default, rename = "release", skip_serializing_if = "Option::is_none")]
+    maybe_release: Option<String>,
+    #[serde(
+        default,
+        rename = "release_heading",
+        skip_serializing_if = "Option::is_none"
+    )]
+    maybe_release_heading: Option<String>,
+    #[serde(
+        default,
+        rename = "change_set_section",
+        skip_serializing_if = "Option::is_none"
+    )]
+    maybe_change_set_section: Option<String>,
+    #[serde(
+        default,
+        rename = "component_section",
+        skip_serializing_if = "Option::is_none"
+    )]
+    maybe_component_section: Option<String>,
+    #[serde(default, rename = "entry", skip_serializing_if = "Option::is_none")]
+    maybe_entry: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    partials: HashMap<String, String>,
+    // The Handlebars registry containing the compiled templates, which is
+    // built the first time it is needed and reset whenever the templates
+    // change.
+    #[serde(skip)]
+    compiled: OnceLock<handlebars::Handlebars<'static>>,
+}
+
+impl PartialEq for TemplatesConfig {
+    fn eq(&self, other: &Self) -> bool {
+        Self::NAMES
+            .iter()
+            .all(|name| self.template(name) == other.template(name))
+            && self.partials == other.partials
+    }
+}
+
+impl TemplatesConfig {
+    /// The template for the entire changelog.
+    pub const CHANGELOG: &'static str = "changelog";
+    /// The template for each release.
+    pub const RELEASE: &'static str = "release";
+    /// The template for each release's heading.
+    pub const RELEASE_HEADING: &'static str = "release_heading";
+    /// The template for each change set section (e.g. "FEATURES").
+    pub const CHANGE_SET_SECTION: &'static str = "change_set_section";
+    /// The template for each component section within a change set section.
+    pub const COMPONENT_SECTION: &'static str = "component_section";
+    /// The template for each entry.
+    pub const ENTRY: &'static str = "entry";
+
+    const NAMES: [&'static str; 6] = [
+        Self::CHANGELOG,
+        Self::RELEASE,
+        Self::RELEASE_HEADING,
+        Self::CHANGE_SET_SECTION,
+        Self::COMPONENT_SECTION,
+        Self::ENTRY,
+    ];
+
+    /// The template with the given name (one of the constants above), if it
+    /// has been configured.
+    pub fn template(&self, name: &str) -> Option<&str> {
+        match name {
+            Self::CHANGELOG => self.maybe_changelog.as_deref(),
+            Self::RELEASE => self.maybe_release.as_deref(),
+            Self::RELEASE_HEADING => self.maybe_release_heading.as_deref(),
+            Self::CHANGE_SET_SECTION => self.maybe_change_set_section.as_deref(),
+            Self::COMPONENT_SECTION => self.maybe_component_section.as_deref(),
+            Self::ENTRY => self.maybe_entry.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets (or, if `None`, removes) the template with the given name (one of
+    /// the constants above).
+    pub fn set_template(&mut self, name: &str, maybe_template: Option<String>) -> Result<()> {
+        let field = match name {
+            Self::CHANGELOG => &mut self.maybe_changelog,
+            Self::RELEASE => &mut self.maybe_release,
+            Self::RELEASE_HEADING => &mut self.maybe_release_heading,
+            Self::CHANGE_SET_SECTION => &mut self.maybe_change_set_section,
+            Self::COMPONENT_SECTION => &mut self.maybe_component_section,
+            Self::ENTRY => &mut self.maybe_entry,
+            _ => return Err(Error::UnknownTemplate(name.to_owned())),
+        };
+        *field = maybe_template;
+        self.compiled = OnceLock::new();
+        Ok(())
+    }
+
+    /// The named partials that can be used from any of the templates.
+    pub fn partials(&self) -> &HashMap<String, String> {
+        &self.partials
+    }
+
+    /// Sets (or, if `None`, removes) the partial with the given name.
+    pub fn set_partial<S: Into<String>>(&mut self, name: S, maybe_partial: Option<String>) {
+        let name = name.into();
+        match maybe_partial {
+            Some(partial) => self.partials.insert(name, partial),
+            None => self.partials.remove(&name),
+        };
+        self.compiled = OnceLock::new();
+    }
+
+    /// Attempt to render the template with the given name using the given
+    /// model, augmented with the fields in `extra` (which must be a JSON
+    /// object).
+    ///
+    /// Returns `None` if no such template has been configured, so that callers
+    /// can fall back to default rendering.
+    pub fn render<T: Serialize>(
+        &self,
+        name: &str,
+        model: &T,
+        extra: serde_json::Value,
+    ) -> Result<Option<String>> {
+        if self.template(name).is_none() {
+            return Ok(None);
+        }
+        let mut data = serde_json::to_value(model)?;
+        if let (Some(data), serde_json::Value::Object(extra)) = (data.as_object_mut(), extra) {
+            data.extend(extra);
+        }
+        self.registry()?
+            .render(name, &data)
+            .map(Some)
+            .map_err(|e| Error::HandlebarsTemplateRender(format!("\"{name}\" template: {e}")))
+    }
+
+    // Returns the registry containing all of the configured templates,
+    // compiling them if they haven't been compiled yet.
+    pub(crate) fn registry(&self) -> Result<&handlebars::Handlebars<'static>> {
+        if let Some(hb) = self.compiled.get() {
+            return Ok(hb);
+        }
+        let hb = self.compile()?;
+        Ok(self.compiled.get_or_init(|| hb))
+    }
+
+    fn compile(&self) -> Result<handlebars::Handlebars<'static>> {
+        let mut hb = handlebars::Handlebars::new();
+        hb.register_escape_fn(handlebars::no_escape);
+        for (name, partial) in &self.partials {
+            hb.register_partial(name, partial)
+                .map_err(|e| Error::HandlebarsTemplateLoad(e.to_string()))?;
+        }
+        for name in Self::NAMES {
+            if let Some(template) = self.template(name) {
+                hb.register_template_string(name, template)
+                    .map_err(|e| Error::HandlebarsTemplateLoad(e.to_string()))?;
+            }
+        }
+        Ok(hb)
+    }
+}
+
+/// Configuration relating to the IDs at the start of entry filenames.
+///
+/// By default, entry filenames must start with the number of the issue or pull
+/// request to which they relate (e.g. `123-some-change.md`). Projects using
+/// external issue trackers can instead specify a pattern matching their ticket
+/// IDs (e.g. `PROJ-1234-some-change.md`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EntryIdsConfig {
+    /// A regular expression matching the ID at the start of each entry's
+    /// filename (e.g. `[A-Z]+-(?P<number>\d+)`), which must be followed by a
+    /// `-` or `.`. If the pattern has a capture group named `id`, only that
+    /// group is used as the entry's ID.
+    #[serde(default, rename = "pattern", skip_serializing_if = "is_default")]
+    pub maybe_pattern: Option<String>,
+    /// The name of the capture group in the pattern by which to sort entries.
+    /// If the captured text is a number, entries are sorted numerically. If not
+    /// specified, entries are sorted by their whole ID.
+    #[serde(default, rename = "sort_key", skip_serializing_if = "is_default")]
+    pub maybe_sort_key: Option<String>,
+}
+
+impl EntryIdsConfig {
+    /// Compiles the configured entry ID pattern, if any.
+    pub(crate) fn pattern(&self) -> Result<Option<EntryIdPattern>> {
+        self.maybe_pattern
+            .as_ref()
+            .map(|pattern| EntryIdPattern::new(pattern, self.maybe_sort_key.clone()))
+            .transpose()
+    }
+}
+
+/// URL templates for references to changes, where `{id}` is replaced by the ID
+/// of the reference and `{project_url}` by the project URL.
+///
+/// These take precedence over the URLs for the project's platform (e.g.
+/// GitHub), and allow for linking to arbitrary issue trackers and code review
+/// tools.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChangeUrlsConfig {
+    /// The URL template for issues (e.g.
+    /// `https://bugzilla.example.com/show_bug.cgi?id={id}`).
+    #[serde(default, rename = "issue", skip_serializing_if = "is_default")]
+    pub maybe_issue: Option<String>,
+    /// The URL template for pull requests (e.g.
+    /// `https://review.example.com/c/project/+/{id}`).
+    #[serde(default, rename = "pull_request", skip_serializing_if = "is_default")]
+    pub maybe_pull_request: Option<String>,
+    /// The URL template for commits (e.g. `{project_url}/+/{id}`).
+    #[serde(default, rename = "commit", skip_serializing_if = "is_default")]
+    pub maybe_commit: Option<String>,
+    /// The URL template for tickets in an external issue tracker (e.g.
+    /// `https://example.atlassian.net/browse/{id}`).
+    #[serde(default, rename = "ticket", skip_serializing_if = "is_default")]
+    pub maybe_ticket: Option<String>,
+}
+
+impl ChangeUrlsConfig {
+    /// Constructs the URL for the given reference from the configured
+    /// templates. Returns `None` if there is no template for this kind of
+    /// reference.
+    pub fn change_url(
+        &self,
+        platform_id: &PlatformId,
+        maybe_project_url: Option<&Url>,
+    ) -> Option<Result<Url>> {
+        let template = match platform_id {
+            PlatformId::Issue(_) => self.maybe_issue.as_ref(),
+            PlatformId::PullRequest(_) => self.maybe_pull_request.as_ref(),
+            PlatformId::Commit(_) => self.maybe_commit.as_ref(),
+            PlatformId::Ticket(_) => self.maybe_ticket.as_ref(),
+        }?;
+        let mut url = template.replace("{id}", &platform_id.id());
+        if url.contains("{project_url}") {
+            let project_url = match maybe_project_url {
+                Some(project_url) => project_url.as_str().trim_end_matches('/').to_owned(),
+                None => return Some(Err(Error::MissingProjectUrl)),
+            };
+            url = url.replace("{project_url}", &project_url);
+        }
+        Some(Url::parse(&url).map_err(Error::from))
+    }
+}
+
+/// Configuration relating to inferring the IDs of new entries, and the issues,
+/// pull requests or tickets to which they relate, from the name of the
+/// currently checked out Git branch (e.g. `1234-fix-timeouts`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BranchesConfig {
+    /// A regular expression that must match the whole branch name. If the
+    /// pattern has a capture group named `id`, only that group is used as the
+    /// entry's ID. Capture groups named `issue`, `pull_request` or `ticket`
+    /// identify the change to which the entry relates.
+    #[serde(default = "BranchesConfig::default_pattern")]
+    pub pattern: String,
+}
+
+impl Default for BranchesConfig {
+    fn default() -> Self {
+        Self {
+            pattern: Self::default_pattern(),
+        }
+    }
+}
+
+impl BranchesConfig {
+    /// Compiles the configured branch name pattern.
+    pub(crate) fn pattern(&self) -> Result<BranchPattern> {
+        BranchPattern::new(&self.pattern)
+    }
+
+    fn default_pattern() -> String {
+        r"(?:.*/)?(?P<id>(?P<issue>[0-9]+)-.+)".to_owned()
+    }
+}
+
+/// Configuration relating to rendering the changelog in the [Keep a
+/// Changelog](https://keepachangelog.com) format.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeepAChangelogConfig {
+    /// Maps change set section IDs (e.g. "bug-fixes") to the Keep a Changelog
+    /// sections in which their entries must be rendered.
+    #[serde(
+        default = "KeepAChangelogConfig::default_sections",
+        skip_serializing_if = "KeepAChangelogConfig::is_default_sections"
+    )]
+    pub sections: HashMap<String, KeepAChangelogSection>,
+    /// The Keep a Changelog section in which to render entries from change
+    /// set sections not mapped in `sections`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub default_section: KeepAChangelogSection,
+}
+
+impl Default for KeepAChangelogConfig {
+    fn default() -> Self {
+        Self {
+            sections: Self::default_sections(),
+            default_section: KeepAChangelogSection::default(),
+        }
+    }
+}
+
+impl KeepAChangelogConfig {
+    /// The Keep a Changelog section in which to render entries from the change
+    /// set section with the given ID.
+    pub fn section_for(&self, change_set_section_id: &str) -> KeepAChangelogSection {
+        self.sections
+            .get(change_set_section_id)
+            .copied()
+            .unwrap_or(self.default_section)
+    }
+
+    fn default_sections() -> HashMap<String, KeepAChangelogSection> {
+        use KeepAChangelogSection::*;
+        [
+            ("features", Added),
+            ("breaking-changes", Changed),
+            ("improvements", Changed),
+            ("enhancements", Changed),
+            ("deprecations", Deprecated),
+            ("deprecated", Deprecated),
+            ("removed", Removed),
+            ("removals", Removed),
+            ("bug-fixes", Fixed),
+            ("fixes", Fixed),
+            ("security", Security),
+        ]
+        .into_iter()
+        .map(|(id, section)| (id.to_owned(), section))
+        .collect()
+    }
+
+    fn is_default_sections(sections: &HashMap<String, KeepAChangelogSection>) -> bool {
+        *sections == Self::default_sections()
+    }
+}
+
+/// The standard sections of a [Keep a Changelog](https://keepachangelog.com)
+/// release, in the order in which they are rendered.
+#[derive(
+    Debug, Clone, Copy, Default, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub enum KeepAChangelogSection {
+    Added,
+    #[default]
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+}
+
+impl fmt::Display for KeepAChangelogSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added => write!(f, "Added"),
+            Self::Changed => write!(f, "Changed"),
+            Self::Deprecated => write!(f, "Deprecated"),
+            Self::Removed => write!(f, "Removed"),
+            Self::Fixed => write!(f, "Fixed"),
+            Self::Security => write!(f, "Security"),
+        }
+    }
+}
+
+/// Configuration relating to linting of entries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LintConfig {
+    /// Overrides the default severity of specific lint rules.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub rules: HashMap<LintRule, LintSeverity>,
+}
+
+impl LintConfig {
+    /// The severity with which to report violations of the given rule.
+    pub fn severity_for(&self, rule: LintRule) -> LintSeverity {
+        self.rules
+            .get(&rule)
+            .copied()
+            .unwrap_or_else(|| rule.default_severity())
+    }
+}
+
+/// The rules checked when linting entries.
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// Entries must start with the configured bullet style.
+    BulletStyle,
+    /// Lines must not be longer than the configured wrap width.
+    LineLength,
+    /// Lines must not end with whitespace.
+    TrailingWhitespace,
+    /// Entries must not contain HTML comments, e.g. leftover placeholders
+    /// from the template used when adding entries interactively.
+    HtmlComment,
+    /// Entries must have some content.
+    EmptyEntry,
+    /// Files in change set sections must have the configured entry extension,
+    /// otherwise they are silently ignored.
+    WrongExtension,
+    /// Entries must be loadable, i.e. have a valid ID at the start of their
+    /// filename and valid front matter.
+    InvalidEntry,
+}
+
+impl LintRule {
+    fn default_severity(&self) -> LintSeverity {
+        match self {
+            Self::LineLength | Self::TrailingWhitespace => LintSeverity::Warning,
+            _ => LintSeverity::Error,
+        }
+    }
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BulletStyle => write!(f, "bullet-style"),
+            Self::LineLength => write!(f, "line-length"),
+            Self::TrailingWhitespace => write!(f, "trailing-whitespace"),
+            Self::HtmlComment => write!(f, "html-comment"),
+            Self::EmptyEntry => write!(f, "empty-entry"),
+            Self::WrongExtension => write!(f, "wrong-extension"),
+            Self::InvalidEntry => write!(f, "invalid-entry"),
+        }
+    }
+}
+
+/// How violations of a lint rule are reported.
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// The rule is not checked.
+    Off,
+    /// Violations are reported, but do not cause linting to fail.
+    Warning,
+    /// Violations are reported and cause linting to fail.
+    Error,
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Configuration relating to scanning the project's Git history for changes
+/// without entries.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScanConfig {
+    /// The section in which to create placeholder entries for changes found
+    /// without entries.
+    #[serde(default = "ScanConfig::default_section")]
+    pub default_section: String,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            default_section: Self::default_section(),
+        }
+    }
+}
+
+impl ScanConfig {
+    fn default_section() -> String {
+        "improvements".to_owned()
+    }
+}
+
+/// Configuration relating to committing and tagging releases.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReleaseConfig {
+    /// The message to use when committing a release, where `{version}` is
+    /// replaced by the release's version (e.g. "v0.2.0").
+    #[serde(default = "ReleaseConfig::default_commit_message")]
+    pub commit_message: String,
+}
+
+impl Default for ReleaseConfig {
+    fn default() -> Self {
+        Self {
+            commit_message: Self::default_commit_message(),
+        }
+    }
+}
+
+impl ReleaseConfig {
+    /// The message to use when committing the release of the given version.
+    pub fn commit_message_for(&self, version: &str) -> String {
+        self.commit_message.replace("{version}", version)
+    }
+
+    fn default_commit_message() -> String {
+        "Release {version}".to_owned()
+    }
+}
+
+/// Configuration relating to crediting the authors of entries. Unless entries
+/// list their authors in their front matter, an entry's author is the author of
+/// the Git commit that added its file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AuthorsConfig {
+    /// Whether to append the handles of each entry's authors to the rendered
+    /// entry, e.g. `(@alice)`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub credit_entries: bool,
+    /// Whether to add a paragraph crediting all of a release's contributors to
+    /// the rendered release.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub contributors: bool,
+    /// The handles (without the `@`) or names of authors who should not be
+    /// credited, e.g. the project's maintainers.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub exclude: Vec<String>,
+    /// Maps commit authors' email addresses to their handles, for authors who
+    /// don't commit using their GitHub `noreply` email address.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub handles: HashMap<String, String>,
+}
+
+impl AuthorsConfig {
+    /// Whether authors need to be obtained from the project's Git history.
+    pub fn is_enabled(&self) -> bool {
+        self.credit_entries || self.contributors
+    }
+
+    /// The credit to give the given commit author, i.e. their handle (e.g.
+    /// `@alice`) if it is known, or otherwise their name. Returns `None` if
+    /// the author is excluded.
+    pub fn credit_for(&self, author: &Author) -> Option<String> {
+        let maybe_handle = self
+            .handles
+            .get(&author.email)
+            .map(String::as_str)
+            .or_else(|| author.github_handle());
+        match maybe_handle {
+            Some(handle) => self.credit_for_handle(handle),
+            None if self.exclude.contains(&author.name) => None,
+            None => Some(author.name.clone()),
+        }
+    }
+
+    /// The credit to give the author with the given handle (with or without
+    /// a leading `@`). Returns `None` if the author is excluded.
+    pub fn credit_for_handle(&self, handle: &str) -> Option<String> {
+        let handle = handle.trim_start_matches('@');
+        if handle.is_empty() || self.exclude.iter().any(|e| e == handle) {
+            return None;
+        }
+        Some(format!("@{handle}"))
+    }
+}
@@ -1,29 +1,119 @@
 use crate::changelog::fs_utils::{path_to_str, read_to_string};
 use crate::changelog::parsing_utils::trim_newlines;
-use crate::{Config, Error, Result};
+use crate::git::Author;
+use crate::{BulletStyle, Config, Error, FrontMatterError, PlatformId, Result};
 use log::debug;
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::ffi::OsStr;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use super::config::SortEntriesBy;
+use super::config::{SortEntriesBy, TemplatesConfig};
 
 /// A single entry in a set of changes.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Entry {
     /// The original filename of this entry.
     pub filename: String,
-    /// The issue/pull request ID relating to this entry.
+    /// The issue/pull request ID relating to this entry (i.e. the first
+    /// number in its filename). If entries are identified by a configured
+    /// pattern instead (see [`crate::EntryIdsConfig`]), this is the numeric
+    /// sort key of the entry's ID, or 0 if it is not numeric.
     pub id: u64,
     /// The content of the entry.
     pub details: String,
+    /// Structured metadata about the entry, parsed from the entry file's
+    /// optional front matter.
+    #[serde(default, skip_serializing_if = "EntryMetadata::is_empty")]
+    pub metadata: EntryMetadata,
+    /// All of the issues/pull requests relating to this entry, obtained from
+    /// its filename (e.g. `123-456-some-change.md` relates to both 123 and 456)
+    /// and its metadata.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub platform_ids: Vec<PlatformId>,
+    /// The author of the Git commit that added this entry's file, if known.
+    /// Only obtained if crediting authors is enabled (see
+    /// [`crate::AuthorsConfig`]).
+    #[serde(rename = "author", default, skip_serializing_if = "Option::is_none")]
+    pub maybe_author: Option<Author>,
+}
+
+/// Optional structured metadata relating to an entry.
+///
+/// This can be specified as TOML front matter (delimited by `+++` lines) at
+/// the start of an entry file, e.g.
+///
+/// ```markdown
+/// +++
+/// authors = ["thanethomson"]
+/// issues = [123]
+/// breaking = true
+/// +++
+/// - Some breaking change
+/// ```
+///
+/// or as YAML front matter (delimited by `---` lines). So as not to mistake a
+/// thematic break for the start of YAML front matter, the opening `---` line
+/// must be directly followed by a `key:` line, e.g.
+///
+/// ```markdown
+/// ---
+/// authors: [thanethomson]
+/// breaking: true
+/// ---
+/// - Some breaking change
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EntryMetadata {
+    /// The authors of the change.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    /// The numbers of the issues relating to the change.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<u32>,
+    /// The numbers of the pull requests relating to the change.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pull_requests: Vec<u32>,
+    /// The IDs of tickets in an external issue tracker relating to the
+    /// change.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tickets: Vec<String>,
+    /// The hashes of the commits relating to the change.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commits: Vec<String>,
+    /// Whether or not this is a breaking change.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub breaking: bool,
+    /// Arbitrary tags associated with the change.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl EntryMetadata {
+    /// Returns whether or not any metadata has been specified.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
 }
 
 impl Entry {
     /// Attempt to read a single entry for a change set section from the given
     /// file.
     pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read_with_id_pattern(path, None)
+    }
+
+    // Reads an entry from the given file, extracting its ID using the given
+    // pattern (if any), or from the numbers at the start of its filename
+    // otherwise.
+    fn read_with_id_pattern<P: AsRef<Path>>(
+        path: P,
+        maybe_pattern: Option<&EntryIdPattern>,
+    ) -> Result<Self> {
         let path = path.as_ref();
         debug!("Loading entry from {}", path.display());
         let orig_id = path
@@ -31,13 +121,69 @@ impl Entry {
             .and_then(OsStr::to_str)
             .ok_or_else(|| Error::CannotObtainName(path_to_str(path)))?
             .to_string();
-        let id = extract_entry_id(&orig_id)?;
+        let (id, ids, maybe_ticket) = ids_from_filename(&orig_id, maybe_pattern)?;
+        let content = read_to_string(path)?;
+        let (metadata, details) = split_front_matter(&content)
+            .map_err(|e| Error::InvalidEntryFrontMatter(path_to_str(path), Box::new(e)))?;
+        let platform_ids = collect_platform_ids(&ids, maybe_ticket, &metadata);
         Ok(Self {
             filename: orig_id,
             id,
-            details: trim_newlines(&read_to_string(path)?).to_owned(),
+            details: trim_newlines(details).to_owned(),
+            metadata,
+            platform_ids,
+            maybe_author: None,
         })
     }
+
+    /// Renders the content of the file for this entry, including its front
+    /// matter (as TOML) if it has any metadata.
+    pub fn file_content(&self) -> Result<String> {
+        with_front_matter(&self.metadata, &self.details)
+    }
+
+    /// Render this entry to a string using the given configuration. Unless a
+    /// template is configured, the markers of the entry's top-level bulleted
+    /// lists are rewritten to use the configured bullet style.
+    pub fn render(&self, config: &Config) -> Result<String> {
+        let credits = self.credits(config);
+        if let Some(rendered) = config.templates.render(
+            TemplatesConfig::ENTRY,
+            self,
+            json!({
+                "bullet": config.bullet_style.to_string(),
+                "credits": credits,
+            }),
+        )? {
+            return Ok(rendered);
+        }
+        let mut rendered = normalize_bullets(&self.details, config.bullet_style);
+        if !config.authors.credit_entries || credits.is_empty() {
+            return Ok(rendered);
+        }
+        let offset = first_text_end(&rendered).unwrap_or(rendered.len());
+        rendered.insert_str(offset, &format!(" ({})", credits.join(", ")));
+        Ok(rendered)
+    }
+
+    /// The credits to give this entry's authors (e.g. `@alice`): the authors
+    /// listed in its metadata if there are any, or otherwise the author of the
+    /// commit that added it. Excluded authors are omitted.
+    pub fn credits(&self, config: &Config) -> Vec<String> {
+        let authors = &config.authors;
+        if !self.metadata.authors.is_empty() {
+            return self
+                .metadata
+                .authors
+                .iter()
+                .filter_map(|handle| authors.credit_for_handle(handle))
+                .collect();
+        }
+        self.maybe_author
+            .iter()
+            .filter_map(|author| authors.credit_for(author))
+            .collect()
+    }
 }
 
 impl fmt::Display for Entry {
@@ -46,49 +192,545 @@ impl fmt::Display for Entry {
     }
 }
 
-fn extract_entry_id<S: AsRef<str>>(s: S) -> Result<u64> {
+// Rewrites the markers of all top-level bulleted list items in the given
+// Markdown to the given bullet style. Nested lists, ordered lists and anything
+// that only looks like a list (e.g. in code blocks) are left untouched.
+fn normalize_bullets(s: &str, bullet_style: BulletStyle) -> String {
+    let bullet = bullet_style.to_string();
+    let mut normalized = s.to_owned();
+    // Whether each of the lists we're currently in is bulleted (as opposed to
+    // ordered), from the outermost to the innermost.
+    let mut lists = Vec::new();
+    for (event, range) in Parser::new(s).into_offset_iter() {
+        match event {
+            Event::Start(Tag::List(maybe_first)) => lists.push(maybe_first.is_none()),
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+            }
+            Event::Start(Tag::Item) if lists == [true] => {
+                // Bullet markers are all single ASCII characters, so this
+                // doesn't change any of the other items' offsets.
+                normalized.replace_range(range.start..range.start + 1, &bullet);
+            }
+            _ => (),
+        }
+    }
+    normalized
+}
+
+// Returns the offset of the end of the text of the first top-level list item
+// (or of the first paragraph, if the given Markdown doesn't start with a list),
+// excluding any nested blocks like sub-lists or code blocks.
+fn first_text_end(s: &str) -> Option<usize> {
+    let mut lists = 0;
+    let mut in_text = false;
+    let mut maybe_end = None;
+    for (event, range) in Parser::new(s).into_offset_iter() {
+        if in_text {
+            match event {
+                // Items in loose lists contain paragraphs.
+                Event::Start(Tag::Paragraph) if maybe_end.is_none() => (),
+                Event::Start(
+                    Tag::Paragraph
+                    | Tag::List(_)
+                    | Tag::CodeBlock(_)
+                    | Tag::BlockQuote(_)
+                    | Tag::Heading { .. }
+                    | Tag::HtmlBlock
+                    | Tag::Table(_),
+                )
+                | Event::End(TagEnd::Paragraph | TagEnd::Item)
+                | Event::Rule => break,
+                _ => maybe_end = Some(range.end),
+            }
+            continue;
+        }
+        match event {
+            Event::Start(Tag::List(_)) => lists += 1,
+            Event::End(TagEnd::List(_)) => lists -= 1,
+            Event::Start(Tag::Item) if lists == 1 => in_text = true,
+            Event::Start(Tag::Paragraph) if lists == 0 => in_text = true,
+            _ => (),
+        }
+    }
+    maybe_end
+}
+
+const TOML_DELIMITER: &str = "+++";
+const YAML_DELIMITER: &str = "---";
+
+// Prepends the given metadata to the given entry details as front matter, if
+// there is any metadata.
+pub(crate) fn with_front_matter(metadata: &EntryMetadata, details: &str) -> Result<String> {
+    if metadata.is_empty() {
+        return Ok(details.to_owned());
+    }
+    let front_matter = toml::to_string(metadata).map_err(Error::TomlSerialize)?;
+    Ok(format!(
+        "{TOML_DELIMITER}\n{front_matter}{TOML_DELIMITER}\n{details}"
+    ))
+}
+
+// Splits the given entry file content into its metadata (parsed from its front
+// matter, if any) and the remaining content.
+pub(crate) fn split_front_matter(
+    content: &str,
+) -> std::result::Result<(EntryMetadata, &str), FrontMatterError> {
+    let content = content.trim_start_matches('\u{feff}');
+    let (first_line, rest) = content.split_once('\n').unwrap_or((content, ""));
+    let delimiter = match first_line.trim_end() {
+        TOML_DELIMITER => TOML_DELIMITER,
+        YAML_DELIMITER if starts_with_yaml_key(rest) => YAML_DELIMITER,
+        _ => return Ok((EntryMetadata::default(), content)),
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == delimiter {
+            let front_matter = &rest[..offset];
+            let metadata = if delimiter == TOML_DELIMITER {
+                toml::from_str(front_matter)?
+            } else {
+                serde_yaml_ng::from_str(front_matter)?
+            };
+            return Ok((metadata, &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    // No closing delimiter, so this isn't front matter.
+    Ok((EntryMetadata::default(), content))
+}
+
+// Whether the given content starts with a YAML mapping key, e.g. `authors:`.
+fn starts_with_yaml_key(content: &str) -> bool {
+    let first_line = content.lines().next().unwrap_or_default();
+    match first_line.split_once(':') {
+        Some((key, value)) => {
+            key.starts_with(|c: char| c.is_ascii_alphabetic())
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && (value.is_empty() || value.starts_with([' ', '\r']))
+        }
+        None => false,
+    }
+}
+
+// Extracts the entry's numeric ID (used for sorting), the issue/pull request
+// numbers and the ticket (if any) from the given entry filename. If an entry
+// ID pattern is given, the filename contains a ticket instead of numbers.
+pub(crate) fn ids_from_filename(
+    filename: &str,
+    maybe_pattern: Option<&EntryIdPattern>,
+) -> Result<(u64, Vec<u64>, Option<String>)> {
+    match maybe_pattern {
+        Some(pattern) => {
+            let (ticket, sort_key) = pattern.extract(filename)?;
+            let id = match sort_key {
+                EntrySortKey::Number(n) => n,
+                EntrySortKey::Text(_) => 0,
+            };
+            Ok((id, Vec::new(), Some(ticket)))
+        }
+        None => {
+            let ids = extract_entry_ids(filename)?;
+            Ok((ids[0], ids, None))
+        }
+    }
+}
+
+// Extracts all of the numbers at the start of the given entry filename, e.g.
+// "123-456-some-change.md" results in `[123, 456]`. Fails if the filename does
+// not start with a number.
+fn extract_entry_ids<S: AsRef<str>>(s: S) -> Result<Vec<u64>> {
     let s = s.as_ref();
     let num_digits = s
         .chars()
         .position(|c| !c.is_ascii_digit())
         .ok_or_else(|| Error::InvalidEntryId(s.to_owned()))?;
-    let digits = &s[..num_digits];
-    Ok(u64::from_str(digits)?)
+    let mut ids = vec![u64::from_str(&s[..num_digits])?];
+    let mut rest = &s[num_digits..];
+    while let Some(remaining) = rest.strip_prefix('-') {
+        let num_digits = remaining
+            .chars()
+            .position(|c| !c.is_ascii_digit())
+            .unwrap_or(remaining.len());
+        // Only whole segments consisting of digits count as IDs.
+        let after = &remaining[num_digits..];
+        if num_digits == 0 || !(after.is_empty() || after.starts_with(['-', '.'])) {
+            break;
+        }
+        ids.push(u64::from_str(&remaining[..num_digits])?);
+        rest = after;
+    }
+    Ok(ids)
+}
+
+/// A compiled pattern for extracting IDs from entry filenames (see
+/// [`crate::EntryIdsConfig`]).
+#[derive(Debug, Clone)]
+pub(crate) struct EntryIdPattern {
+    regex: Regex,
+    maybe_sort_key: Option<String>,
+}
+
+impl EntryIdPattern {
+    pub(crate) fn new(pattern: &str, maybe_sort_key: Option<String>) -> Result<Self> {
+        // The pattern must always match from the start of the filename, up to
+        // a "-" or "." (or the end of the filename), so that it can't match
+        // just part of a longer ID.
+        let regex = Regex::new(&format!("^({pattern})(?:[-.]|$)"))
+            .map_err(|e| Error::InvalidEntryIdPattern(e.to_string()))?;
+        if let Some(sort_key) = &maybe_sort_key {
+            if !regex.capture_names().flatten().any(|name| name == sort_key) {
+                return Err(Error::InvalidEntryIdPattern(format!(
+                    "no capture group named \"{sort_key}\" in pattern \"{pattern}\""
+                )));
+            }
+        }
+        Ok(Self {
+            regex,
+            maybe_sort_key,
+        })
+    }
+
+    // Extracts the ID and sort key from the given entry filename.
+    fn extract(&self, filename: &str) -> Result<(String, EntrySortKey)> {
+        let captures = self.regex.captures(filename).ok_or_else(|| {
+            Error::EntryIdPatternMismatch(filename.to_owned(), self.regex.to_string())
+        })?;
+        let id = captures
+            .name("id")
+            .or_else(|| captures.get(1))
+            .map(|m| m.as_str().to_owned())
+            .unwrap_or_default();
+        let sort_key = self
+            .maybe_sort_key
+            .as_ref()
+            .and_then(|sort_key| captures.name(sort_key))
+            .map(|m| m.as_str())
+            .unwrap_or(&id);
+        let sort_key = match u64::from_str(sort_key) {
+            Ok(n) => EntrySortKey::Number(n),
+            Err(_) => EntrySortKey::Text(sort_key.to_owned()),
+        };
+        Ok((id, sort_key))
+    }
+}
+
+// Numeric sort keys are ordered before textual ones.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum EntrySortKey {
+    Number(u64),
+    Text(String),
+}
+
+// Combines the IDs from an entry's filename with the issues/pull requests and
+// tickets from its metadata. Numeric IDs from the filename are assumed to be
+// issues unless the metadata says they are pull requests.
+pub(crate) fn collect_platform_ids(
+    ids: &[u64],
+    maybe_ticket: Option<String>,
+    metadata: &EntryMetadata,
+) -> Vec<PlatformId> {
+    let mut platform_ids = ids
+        .iter()
+        .filter_map(|id| u32::try_from(*id).ok())
+        .filter(|id| *id > 0)
+        .map(|id| {
+            if metadata.pull_requests.contains(&id) {
+                PlatformId::PullRequest(id)
+            } else {
+                PlatformId::Issue(id)
+            }
+        })
+        .chain(maybe_ticket.map(PlatformId::Ticket))
+        .collect::<Vec<PlatformId>>();
+    let from_metadata = metadata
+        .issues
+        .iter()
+        .map(|no| PlatformId::Issue(*no))
+        .chain(
+            metadata
+                .pull_requests
+                .iter()
+                .map(|no| PlatformId::PullRequest(*no)),
+        )
+        .chain(metadata.tickets.iter().cloned().map(PlatformId::Ticket))
+        .chain(metadata.commits.iter().cloned().map(PlatformId::Commit))
+        .collect::<Vec<PlatformId>>();
+    for platform_id in from_metadata {
+        if !platform_ids.contains(&platform_id) {
+            platform_ids.push(platform_id);
+        }
+    }
+    platform_ids
 }
 
 pub(crate) fn read_entries_sorted(
     entry_files: Vec<PathBuf>,
     config: &Config,
 ) -> Result<Vec<Entry>> {
+    let maybe_pattern = config.entry_ids.pattern()?;
     let mut entries = entry_files
         .into_iter()
-        .map(Entry::read_from_file)
+        .map(|path| Entry::read_with_id_pattern(path, maybe_pattern.as_ref()))
         .collect::<Result<Vec<Entry>>>()?;
-    // Sort entries by ID in ascending numeric order.
-    entries.sort_by(|a, b| match config.change_set_sections.sort_entries_by {
-        SortEntriesBy::ID => a.id.cmp(&b.id),
-        SortEntriesBy::EntryText => a.details.cmp(&b.details),
-    });
+    match (&config.change_set_sections.sort_entries_by, &maybe_pattern) {
+        // Sort entries by the sort key extracted from their IDs.
+        (SortEntriesBy::ID, Some(pattern)) => entries.sort_by_cached_key(|e| {
+            pattern
+                .extract(&e.filename)
+                .map(|(_, sort_key)| sort_key)
+                .ok()
+        }),
+        // Sort entries by ID in ascending numeric order.
+        (SortEntriesBy::ID, None) => entries.sort_by_key(|e| e.id),
+        (SortEntriesBy::EntryText, _) => entries.sort_by(|a, b| a.details.cmp(&b.details)),
+    }
     Ok(entries)
 }
 
 #[cfg(test)]
 mod test {
-    use super::extract_entry_id;
+    use super::{
+        collect_platform_ids, extract_entry_ids, first_text_end, normalize_bullets,
+        split_front_matter, EntryIdPattern, EntryMetadata, EntrySortKey,
+    };
+    use crate::{BulletStyle, PlatformId};
 
     #[test]
     fn entry_id_extraction() {
         let cases = vec![
-            ("830-something.md", 830_u64),
-            ("1.md", 1_u64),
-            ("0128-another-issue.md", 128_u64),
+            ("830-something.md", vec![830_u64]),
+            ("1.md", vec![1_u64]),
+            ("0128-another-issue.md", vec![128_u64]),
+            ("123-456-some-change.md", vec![123_u64, 456]),
+            ("1-2-3.md", vec![1_u64, 2, 3]),
+            ("100-2021-roadmap.md", vec![100_u64, 2021]),
+            ("12-34abc-5-change.md", vec![12_u64]),
         ];
 
         for (s, expected) in cases {
-            let actual = extract_entry_id(s).unwrap();
+            let actual = extract_entry_ids(s).unwrap();
             assert_eq!(expected, actual);
         }
 
-        assert!(extract_entry_id("no-number").is_err());
+        assert!(extract_entry_ids("no-number").is_err());
+    }
+
+    #[test]
+    fn platform_id_collection() {
+        let metadata = EntryMetadata {
+            issues: vec![123, 789],
+            pull_requests: vec![456],
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                PlatformId::Issue(123),
+                PlatformId::PullRequest(456),
+                PlatformId::Issue(789)
+            ],
+            collect_platform_ids(&[123, 456], None, &metadata)
+        );
+        assert_eq!(
+            vec![PlatformId::Issue(1)],
+            collect_platform_ids(&[1], None, &EntryMetadata::default())
+        );
+        assert!(collect_platform_ids(&[0], None, &EntryMetadata::default()).is_empty());
+        assert_eq!(
+            vec![
+                PlatformId::Ticket("PROJ-1234".to_owned()),
+                PlatformId::Ticket("PROJ-1".to_owned())
+            ],
+            collect_platform_ids(
+                &[],
+                Some("PROJ-1234".to_owned()),
+                &EntryMetadata {
+                    tickets: vec!["PROJ-1234".to_owned(), "PROJ-1".to_owned()],
+                    ..Default::default()
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn entry_id_pattern_extraction() {
+        let pattern =
+            EntryIdPattern::new(r"[A-Z]+-(?P<number>\d+)", Some("number".to_owned())).unwrap();
+        assert_eq!(
+            ("PROJ-1234".to_owned(), EntrySortKey::Number(1234)),
+            pattern.extract("PROJ-1234-fix-timeout.md").unwrap()
+        );
+        assert!(pattern.extract("fix-PROJ-1234.md").is_err());
+        // The pattern must match the whole ID, not just a prefix of it.
+        assert!(pattern.extract("PROJ-12x-fix.md").is_err());
+        assert_eq!(
+            ("PROJ-12".to_owned(), EntrySortKey::Number(12)),
+            pattern.extract("PROJ-12.md").unwrap()
+        );
+
+        let pattern = EntryIdPattern::new(r"(?P<id>[a-z]+)_\d+", None).unwrap();
+        assert_eq!(
+            ("eng".to_owned(), EntrySortKey::Text("eng".to_owned())),
+            pattern.extract("eng_12-fix-timeout.md").unwrap()
+        );
+
+        assert!(EntryIdPattern::new(r"[A-Z]+-\d+", Some("number".to_owned())).is_err());
+        assert!(EntryIdPattern::new(r"[A-Z", None).is_err());
+    }
+
+    #[test]
+    fn front_matter_parsing() {
+        let expected = EntryMetadata {
+            authors: vec!["alice".to_owned(), "bob".to_owned()],
+            issues: vec![123],
+            pull_requests: vec![456, 789],
+            tickets: Vec::new(),
+            commits: vec!["1a2b3c4".to_owned()],
+            breaking: true,
+            tags: vec!["api".to_owned()],
+        };
+        let toml_content = r#"+++
+authors = ["alice", "bob"]
+issues = [123]
+pull_requests = [456, 789]
+commits = ["1a2b3c4"]
+breaking = true
+tags = ["api"]
++++
+- Some change
+"#;
+        let crlf_content = toml_content.replace('\n', "\r\n");
+        let (metadata, details) = split_front_matter(toml_content).unwrap();
+        assert_eq!(expected, metadata);
+        assert_eq!("- Some change\n", details);
+        let (metadata, details) = split_front_matter(&crlf_content).unwrap();
+        assert_eq!(expected, metadata);
+        assert_eq!("- Some change\r\n", details);
+
+        let (metadata, details) = split_front_matter("- Some change\n").unwrap();
+        assert!(metadata.is_empty());
+        assert_eq!("- Some change\n", details);
+        // Without a closing delimiter, there is no front matter.
+        let (metadata, details) = split_front_matter("+++\n- Some change\n").unwrap();
+        assert!(metadata.is_empty());
+        assert_eq!("+++\n- Some change\n", details);
+        // Thematic breaks are just Markdown.
+        let content = "---\n- Some change\n\n---\n\nMore details\n";
+        let (metadata, details) = split_front_matter(content).unwrap();
+        assert!(metadata.is_empty());
+        assert_eq!(content, details);
+
+        let yaml_content = r#"---
+authors: [alice, bob]
+issues: [123]
+pull_requests:
+  - 456
+  - 789
+commits: ["1a2b3c4"]
+breaking: true
+tags: [api]
+---
+- Some change
+"#;
+        let (metadata, details) = split_front_matter(yaml_content).unwrap();
+        assert_eq!(expected, metadata);
+        assert_eq!("- Some change\n", details);
+        let crlf_content = yaml_content.replace('\n', "\r\n");
+        let (metadata, details) = split_front_matter(&crlf_content).unwrap();
+        assert_eq!(expected, metadata);
+        assert_eq!("- Some change\r\n", details);
+        // A thematic break followed by text that merely contains a colon is
+        // not YAML front matter.
+        let content = "---\nSee https://example.com for details\n---\n";
+        let (metadata, details) = split_front_matter(content).unwrap();
+        assert!(metadata.is_empty());
+        assert_eq!(content, details);
+
+        assert!(split_front_matter("+++\nbreaking = 1\n+++\n- Some change").is_err());
+        assert!(split_front_matter("---\nbreaking: 1\n---\n- Some change").is_err());
+    }
+
+    #[test]
+    fn bullet_normalization() {
+        let cases = vec![
+            ("- Already a dash", "- Already a dash"),
+            (
+                "* An asterisk\n  that overflows",
+                "- An asterisk\n  that overflows",
+            ),
+            (
+                "* Multiple\n+ top-level\n- items\n  * with a nested one",
+                "- Multiple\n- top-level\n- items\n  * with a nested one",
+            ),
+            (
+                "* Code\n\n  ```\n  * not a bullet\n  ```\n\n```\n* nor this\n```",
+                "- Code\n\n  ```\n  * not a bullet\n  ```\n\n```\n* nor this\n```",
+            ),
+            ("1. Ordered\n   * nested", "1. Ordered\n   * nested"),
+            ("**Bold** text", "**Bold** text"),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(normalize_bullets(s, BulletStyle::Dash), expected);
+        }
+        assert_eq!(
+            normalize_bullets("- A dash", BulletStyle::Asterisk),
+            "* A dash"
+        );
+    }
+
+    #[test]
+    fn first_text_end_location() {
+        let cases = vec![
+            ("- A change", "- A change"),
+            (
+                "- A *change*
+  over two lines",
+                "- A *change*
+  over two lines",
+            ),
+            (
+                "- A change
+  - with a sub-bullet",
+                "- A change",
+            ),
+            (
+                "- A change
+
+  ```
+  code
+  ```",
+                "- A change",
+            ),
+            (
+                "- A change
+
+  Another paragraph",
+                "- A change",
+            ),
+            (
+                "- A change
+- Another change",
+                "- A change",
+            ),
+            (
+                "A paragraph
+
+```
+code
+```",
+                "A paragraph",
+            ),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(&s[..first_text_end(s).unwrap()], expected, "{s}");
+        }
+        assert_eq!(
+            first_text_end(
+                "```
+code
+```"
+            ),
+            None
+        );
     }
 }
@@ -0,0 +1,210 @@
+//! Formatting of changelog entry files.
+
+use crate::changelog::entry::split_front_matter;
+use crate::fs_utils::read_to_string;
+use crate::{Changelog, Config, Result};
+use std::path::{Path, PathBuf};
+
+impl Changelog {
+    /// Formats all of the entry files of this changelog, which was loaded from
+    /// the given path, without modifying them. Returns the path and formatted
+    /// content of each entry file whose content would change.
+    ///
+    /// Formatting rewraps each entry's first paragraph to the configured wrap
+    /// width, normalizes its bullet to the configured bullet style, normalizes
+    /// line endings and trims trailing blank lines.
+    pub fn format_entries<P: AsRef<Path>>(
+        &self,
+        config: &Config,
+        path: P,
+    ) -> Result<Vec<(PathBuf, String)>> {
+        let path = path.as_ref();
+        let mut unformatted = Vec::new();
+        for entry_path in self.entries() {
+            let entry_file = path.join(entry_path.as_path(config));
+            let content = read_to_string(&entry_file)?;
+            let formatted = format_entry_file(config, &content);
+            if formatted != content {
+                unformatted.push((entry_file, formatted));
+            }
+        }
+        Ok(unformatted)
+    }
+}
+
+// Formats the given content of an entry file, leaving any front matter as-is.
+pub(crate) fn format_entry_file(config: &Config, content: &str) -> String {
+    let content = content.replace("\r\n", "\n").replace('\r', "\n");
+    let details = split_front_matter(&content)
+        .map(|(_, details)| details)
+        .unwrap_or(&content);
+    let front_matter = &content[..content.len() - details.len()];
+
+    let lines = details.lines().collect::<Vec<_>>();
+    let mut formatted = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+        if is_code_fence(line) {
+            // Copy code blocks verbatim, up to and including the closing
+            // fence.
+            formatted.push(line.to_owned());
+            while i < lines.len() {
+                formatted.push(lines[i].to_owned());
+                i += 1;
+                if is_code_fence(lines[i - 1]) {
+                    break;
+                }
+            }
+            continue;
+        }
+        let Some(text) = strip_bullet(line) else {
+            formatted.push(line.to_owned());
+            continue;
+        };
+        // Gather the rest of the item's first paragraph.
+        let mut paragraph = vec![text.trim()];
+        while i < lines.len() && is_paragraph_continuation(lines[i]) {
+            paragraph.push(lines[i].trim());
+            i += 1;
+        }
+        formatted.extend(wrap_item(config, &paragraph.join(" ")));
+    }
+    while formatted.last().is_some_and(|line| line.trim().is_empty()) {
+        formatted.pop();
+    }
+    // Entries created by `unclog add` have no trailing newline, so only keep
+    // one if there was one to start with.
+    let newline = if content.ends_with('\n') { "\n" } else { "" };
+    format!("{front_matter}{}{newline}", formatted.join("\n"))
+}
+
+fn wrap_item(config: &Config, text: &str) -> Vec<String> {
+    let item = format!("{} {}", config.bullet_style, text);
+    let wrapped = textwrap::wrap(
+        &item,
+        textwrap::Options::new(config.wrap as usize)
+            .subsequent_indent("  ")
+            .break_words(false)
+            .word_separator(textwrap::WordSeparator::AsciiSpace),
+    );
+    let mut lines: Vec<String> = Vec::new();
+    for line in wrapped {
+        let Some(prev) = lines.last_mut() else {
+            lines.push(line.into_owned());
+            continue;
+        };
+        // Words that would start some other block (e.g. a nested list) at the
+        // start of a line are kept on the previous line instead.
+        let mut rest = line.trim_start();
+        while let Some(word) = rest.split(' ').next().filter(|w| starts_block(w)) {
+            prev.push(' ');
+            prev.push_str(word);
+            rest = rest[word.len()..].trim_start();
+        }
+        if !rest.is_empty() {
+            lines.push(format!("  {rest}"));
+        }
+    }
+    lines
+}
+
+// Whether a line starting with the given word would start some other block
+// (e.g. a list item, heading, block quote or code block) or turn the previous
+// line into a heading, instead of continuing a paragraph.
+fn starts_block(word: &str) -> bool {
+    let is_ordered_marker = word
+        .strip_suffix(['.', ')'])
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    let is_repeated = |c: char| !word.is_empty() && word.chars().all(|w| w == c);
+    matches!(word, "-" | "*" | "+")
+        || is_ordered_marker
+        || (is_repeated('#') && word.len() <= 6)
+        || is_repeated('-')
+        || is_repeated('=')
+        || ((is_repeated('*') || is_repeated('_')) && word.len() >= 3)
+        || word.starts_with('>')
+        || is_code_fence(word)
+}
+
+fn strip_bullet(line: &str) -> Option<&str> {
+    line.strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+}
+
+fn is_code_fence(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+// Whether the given line continues the paragraph of a top-level list item, as
+// opposed to ending it or starting some other block (e.g. a nested list).
+fn is_paragraph_continuation(line: &str) -> bool {
+    if line.trim().is_empty() || !line.starts_with(char::is_whitespace) {
+        return false;
+    }
+    let line = line.trim_start();
+    let starts_list_item = strip_bullet(line).is_some()
+        || line.split_once(['.', ')']).is_some_and(|(n, rest)| {
+            !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) && rest.starts_with(' ')
+        });
+    !(starts_list_item || is_code_fence(line) || line.starts_with(['>', '#', '|']))
+}
+
+#[cfg(test)]
+mod test {
+    use super::format_entry_file;
+    use crate::{BulletStyle, Config};
+
+    #[test]
+    fn entry_file_formatting() {
+        let config = Config {
+            wrap: 30,
+            ..Config::default()
+        };
+        let cases = [
+            ("- Already formatted\n", "- Already formatted\n"),
+            ("* Asterisk\r\n\r\n\n", "- Asterisk\n"),
+            (
+                "- A rather long entry that needs to be rewrapped ([#1](https://example.com/1))",
+                "- A rather long entry that\n  needs to be rewrapped\n  ([#1](https://example.com/1))",
+            ),
+            (
+                "- Short\n  lines\n  joined\n  - Nested item\n    untouched\n",
+                "- Short lines joined\n  - Nested item\n    untouched\n",
+            ),
+            (
+                "+++\nbreaking = true\n+++\n* Entry with\n  front matter\n",
+                "+++\nbreaking = true\n+++\n- Entry with front matter\n",
+            ),
+            // Wrapping must not turn text into nested list items or headings.
+            (
+                "- Keep list markers such as a - b",
+                "- Keep list markers such as a -\n  b",
+            ),
+            (
+                "- Keep the ordered list marker 1. b",
+                "- Keep the ordered list marker 1.\n  b",
+            ),
+            (
+                "- Do not wrap before headings # b",
+                "- Do not wrap before headings #\n  b",
+            ),
+            (
+                "- Code:\n  ```\n  * not a bullet that should be touched\n  ```\n",
+                "- Code:\n  ```\n  * not a bullet that should be touched\n  ```\n",
+            ),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(format_entry_file(&config, content), expected);
+        }
+
+        let config = Config {
+            bullet_style: BulletStyle::Asterisk,
+            ..Config::default()
+        };
+        assert_eq!(format_entry_file(&config, "- Dash"), "* Dash");
+    }
+}
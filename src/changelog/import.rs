@@ -0,0 +1,450 @@
+//! Importing of existing Markdown changelogs into `unclog`'s directory
+//! structure.
+
+use crate::changelog::entry::{collect_platform_ids, ids_from_filename};
+use crate::changelog::parsing_utils::{extract_release_version, slugify, trim_newlines};
+use crate::fs_utils::{ensure_dir, file_exists, path_to_str, read_to_string};
+use crate::{
+    ChangeSet, ChangeSetSection, Changelog, ComponentSection, Config, Entry, EntryMetadata, Error,
+    Release, Result, Version,
+};
+use chrono::NaiveDate;
+use log::{debug, info, warn};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+impl Changelog {
+    /// Attempts to import the existing Markdown changelog at `changelog_path`
+    /// into the changelog directory at `path`, writing out a folder for each
+    /// release and section, and a file for each entry.
+    pub fn import<P, Q>(config: &Config, path: P, changelog_path: Q) -> Result<()>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let changelog_path = changelog_path.as_ref();
+        info!("Importing changelog from: {}", path_to_str(changelog_path));
+        let changelog = Self::parse_markdown(config, read_to_string(changelog_path)?)?;
+        changelog.write_to_dir(config, path)
+    }
+
+    /// Attempts to parse a changelog from the given Markdown content.
+    ///
+    /// Level 2 headings are assumed to be releases (or the unreleased
+    /// changes), and level 3 headings are assumed to be change set sections.
+    /// Top-level bullets within sections are assumed to be entries, unless
+    /// they refer to a configured component (or the general entries title), in
+    /// which case their nested bullets are assumed to be entries for that
+    /// component. Content appearing before the first release is treated as the
+    /// prologue, and content from the first level 2 heading that cannot be
+    /// parsed as a release onwards is treated as the epilogue.
+    ///
+    /// Fails if a section is not one of the configured change set sections,
+    /// or if an entry's filename would not match the configured entry ID
+    /// pattern, since the imported changelog could not be loaded otherwise.
+    pub fn parse_markdown<S: AsRef<str>>(config: &Config, content: S) -> Result<Self> {
+        let blocks = split_by_heading(content.as_ref().lines(), "## ");
+        let mut prologue_lines = blocks.preamble;
+        // Skip the changelog's own heading, if present.
+        if let Some(first) = prologue_lines.iter().position(|l| !l.trim().is_empty()) {
+            if prologue_lines[first].starts_with("# ") {
+                prologue_lines.drain(..=first);
+            }
+        }
+        let mut changelog = Self {
+            maybe_unreleased: None,
+            releases: Vec::new(),
+            prologue: non_empty(prologue_lines.join("\n")),
+            epilogue: None,
+        };
+        for (i, (heading, lines)) in blocks.sections.iter().enumerate() {
+            if heading.to_lowercase().contains("unreleased") {
+                debug!("Parsing unreleased changes");
+                changelog.maybe_unreleased = Some(parse_change_set(config, lines, None)?);
+                continue;
+            }
+            match parse_release_heading(config, heading) {
+                Some((id, version, maybe_date)) => {
+                    debug!("Parsing release {id}");
+                    let changes = parse_change_set(config, lines, maybe_date)?;
+                    changelog.releases.push(Release {
+                        id,
+                        version,
+                        maybe_date,
+                        changes,
+                    });
+                }
+                None => {
+                    warn!("Cannot parse \"## {heading}\" as a release heading: treating it and everything after it as the epilogue");
+                    let epilogue = blocks.sections[i..]
+                        .iter()
+                        .flat_map(|(heading, lines)| {
+                            std::iter::once(format!("## {heading}")).chain(lines.iter().cloned())
+                        })
+                        .collect::<Vec<String>>()
+                        .join("\n");
+                    changelog.epilogue = non_empty(epilogue);
+                    break;
+                }
+            }
+        }
+        Ok(changelog)
+    }
+
+    /// Writes this changelog out to the given changelog directory. Fails if
+    /// any of the release directories already exist.
+    pub fn write_to_dir<P: AsRef<Path>>(&self, config: &Config, path: P) -> Result<()> {
+        let path = path.as_ref();
+        ensure_dir(path)?;
+        for release in &self.releases {
+            let release_path = path.join(&release.id);
+            if fs::metadata(&release_path).is_ok() {
+                return Err(Error::DirExists(path_to_str(&release_path)));
+            }
+        }
+        if let Some(unreleased) = &self.maybe_unreleased {
+            write_change_set(config, &path.join(&config.unreleased.folder), unreleased)?;
+        }
+        for release in &self.releases {
+            write_change_set(config, &path.join(&release.id), &release.changes)?;
+        }
+        if let Some(prologue) = &self.prologue {
+            write_new_file(&path.join(&config.prologue_filename), prologue)?;
+        }
+        if let Some(epilogue) = &self.epilogue {
+            write_new_file(&path.join(&config.epilogue_filename), epilogue)?;
+        }
+        info!("Wrote changelog to: {}", path_to_str(path));
+        Ok(())
+    }
+}
+
+struct HeadingBlocks {
+    // Lines before the first heading.
+    preamble: Vec<String>,
+    // Each heading's text, along with the lines following it.
+    sections: Vec<(String, Vec<String>)>,
+}
+
+// Splits the given lines into blocks delimited by headings starting with the
+// given prefix, ignoring any such headings within fenced code blocks.
+fn split_by_heading<'a, I>(lines: I, prefix: &str) -> HeadingBlocks
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut blocks = HeadingBlocks {
+        preamble: Vec::new(),
+        sections: Vec::new(),
+    };
+    let mut in_code_block = false;
+    for line in lines {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        if !in_code_block && line.starts_with(prefix) {
+            blocks
+                .sections
+                .push((line[prefix.len()..].trim().to_owned(), Vec::new()));
+            continue;
+        }
+        match blocks.sections.last_mut() {
+            Some((_, section_lines)) => section_lines.push(line.to_owned()),
+            None => blocks.preamble.push(line.to_owned()),
+        }
+    }
+    blocks
+}
+
+// Parses a release heading like "v0.2.0", "[0.2.0] - 2024-01-01",
+// "[0.2.0](https://...) - 2024-01-01" or "v0.2.0 (2024-01-01)" into the
+// release ID, version and optional date.
+fn parse_release_heading(
+    config: &Config,
+    heading: &str,
+) -> Option<(String, Version, Option<NaiveDate>)> {
+    let (id, rest) = match heading.strip_prefix('[').and_then(|h| h.split_once(']')) {
+        // Leave out the link target if the release is linked.
+        Some((id, rest)) => match rest.strip_prefix('(') {
+            Some(link) => (id, link.split_once(')').map_or("", |(_, rest)| rest)),
+            None => (id, rest),
+        },
+        None => heading.split_once(' ').unwrap_or((heading, "")),
+    };
+    let id = id.trim().to_owned();
+    let version = Version::parse(extract_release_version(&id).ok()?).ok()?;
+    let rest = rest.trim_matches(|c: char| c.is_whitespace() || "-–()[]".contains(c));
+    let maybe_date = if rest.is_empty() {
+        None
+    } else {
+        config
+            .release_date_formats
+            .0
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once("%F"))
+            .find_map(|fmt| NaiveDate::parse_from_str(rest, fmt).ok())
+    };
+    Some((id, version, maybe_date))
+}
+
+fn parse_change_set(
+    config: &Config,
+    lines: &[String],
+    maybe_date: Option<NaiveDate>,
+) -> Result<ChangeSet> {
+    let blocks = split_by_heading(lines.iter().map(String::as_str), "### ");
+    let mut summary = trim_blank_lines(&blocks.preamble).join("\n");
+    // Make sure that the date from the release heading ends up in the first
+    // line of the summary, where `unclog` expects to find it.
+    if let Some(date) = maybe_date {
+        let date_fmt = config
+            .release_date_formats
+            .0
+            .first()
+            .map(String::as_str)
+            .unwrap_or("%F");
+        let date = date.format(date_fmt).to_string();
+        summary = if summary.is_empty() {
+            date
+        } else {
+            format!("{date}\n\n{summary}")
+        };
+    }
+    let sections = blocks
+        .sections
+        .iter()
+        .map(|(title, lines)| parse_section(config, title, lines))
+        .collect::<Result<Vec<ChangeSetSection>>>()?;
+    Ok(ChangeSet {
+        maybe_summary: non_empty(summary),
+        sections,
+    })
+}
+
+fn parse_section(config: &Config, title: &str, lines: &[String]) -> Result<ChangeSetSection> {
+    let id = config
+        .change_set_sections
+        .all
+        .iter()
+        .find(|(_, s)| s.maybe_title.as_deref() == Some(title))
+        .map(|(id, _)| id.clone())
+        .unwrap_or_else(|| slugify(title, usize::MAX));
+    if !config.change_set_sections.is_defined(&id) {
+        return Err(Error::SectionNotDefined(id));
+    }
+    let mut section = ChangeSetSection {
+        id,
+        title: title.to_owned(),
+        maybe_description: None,
+        entries: Vec::new(),
+        component_sections: Vec::new(),
+    };
+    let mut filenames = HashSet::new();
+    for item in split_bullets(lines) {
+        let first_line = item[0][2..].trim();
+        if first_line == config.components.general_entries_title {
+            let entries = dedent(&item[1..], config.components.entry_indent as usize);
+            section
+                .entries
+                .extend(parse_entries(config, &entries, &mut filenames)?);
+            continue;
+        }
+        match find_component(config, first_line) {
+            Some((id, name, maybe_path)) => {
+                let entries = dedent(&item[1..], config.components.entry_indent as usize);
+                let mut component_filenames = HashSet::new();
+                section.component_sections.push(ComponentSection {
+                    id,
+                    name,
+                    maybe_path,
+                    entries: parse_entries(config, &entries, &mut component_filenames)?,
+                });
+            }
+            None => section
+                .entries
+                .push(parse_entry(config, &item, &mut filenames)?),
+        }
+    }
+    Ok(section)
+}
+
+fn parse_entries(
+    config: &Config,
+    lines: &[String],
+    filenames: &mut HashSet<String>,
+) -> Result<Vec<Entry>> {
+    split_bullets(lines)
+        .into_iter()
+        .map(|item| parse_entry(config, &item, filenames))
+        .collect()
+}
+
+fn parse_entry(
+    config: &Config,
+    lines: &[String],
+    filenames: &mut HashSet<String>,
+) -> Result<Entry> {
+    let details = trim_blank_lines(lines).join("\n");
+    let first_line = &lines[0][2..];
+    let maybe_id = extract_reference_number(&details);
+    let id = maybe_id.unwrap_or_default();
+    // Leave out any references from the slug.
+    let slug = slugify(first_line.split("([").next().unwrap_or_default(), 6);
+    let mut filename = format!("{id}-{slug}.{}", config.change_sets.entry_ext);
+    let mut i = 2;
+    while filenames.contains(&filename) {
+        filename = format!("{id}-{slug}-{i}.{}", config.change_sets.entry_ext);
+        i += 1;
+    }
+    filenames.insert(filename.clone());
+    // Make sure that the entry can be loaded again.
+    ids_from_filename(&filename, config.entry_ids.pattern()?.as_ref())?;
+    if maybe_id.is_none() {
+        warn!("No issue/PR reference found in entry \"{first_line}\": writing it as {filename}");
+    }
+    let metadata = EntryMetadata::default();
+    Ok(Entry {
+        filename,
+        id,
+        details,
+        platform_ids: collect_platform_ids(&[id], None, &metadata),
+        maybe_author: None,
+        metadata,
+    })
+}
+
+// Finds the configured component whose name matches the given text, which
+// may be rendered as a Markdown hyperlink.
+fn find_component(config: &Config, text: &str) -> Option<(String, String, Option<String>)> {
+    let name = text
+        .strip_prefix('[')
+        .and_then(|t| t.split_once("]("))
+        .map(|(name, _)| name)
+        .unwrap_or(text);
+    let (id, component) = config
+        .components
+        .all
+        .iter()
+        .find(|(id, c)| c.name == name || *id == name)?;
+    Some((
+        id.clone(),
+        component.name.clone(),
+        component.maybe_path.as_ref().map(path_to_str),
+    ))
+}
+
+// Splits the given lines into top-level bulleted items, ignoring any content
+// preceding the first bullet.
+fn split_bullets(lines: &[String]) -> Vec<Vec<String>> {
+    let mut items: Vec<Vec<String>> = Vec::new();
+    for line in lines {
+        if line.starts_with("- ") || line.starts_with("* ") {
+            items.push(vec![line.clone()]);
+        } else if let Some(item) = items.last_mut() {
+            item.push(line.clone());
+        } else if !line.trim().is_empty() {
+            warn!("Ignoring content outside of any entry: {line}");
+        }
+    }
+    items.into_iter().map(|i| trim_blank_lines(&i)).collect()
+}
+
+fn dedent(lines: &[String], indent: usize) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| {
+            let spaces = line.len() - line.trim_start_matches(' ').len();
+            line[spaces.min(indent)..].to_owned()
+        })
+        .collect()
+}
+
+fn trim_blank_lines(lines: &[String]) -> Vec<String> {
+    let start = lines.iter().position(|l| !l.trim().is_empty());
+    let end = lines.iter().rposition(|l| !l.trim().is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end]
+            .iter()
+            .map(|l| l.trim_end().to_owned())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Extracts the first issue/PR number referenced in the given entry text by
+// way of a link like `[#123](...)` or `[\#123](...)`.
+fn extract_reference_number(s: &str) -> Option<u64> {
+    s.match_indices('[').find_map(|(i, _)| {
+        let rest = s[i + 1..].trim_start_matches('\\').strip_prefix('#')?;
+        let digits = rest
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect::<String>();
+        digits.parse().ok()
+    })
+}
+
+fn non_empty(s: String) -> Option<String> {
+    let s = trim_newlines(s.trim_start_matches(['\n', '\r'])).to_owned();
+    if s.trim().is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+fn write_change_set(config: &Config, path: &Path, change_set: &ChangeSet) -> Result<()> {
+    ensure_dir(path)?;
+    if let Some(summary) = &change_set.maybe_summary {
+        write_new_file(&path.join(&config.change_sets.summary_filename), summary)?;
+    }
+    for section in &change_set.sections {
+        let section_path = path.join(&section.id);
+        ensure_dir(&section_path)?;
+        write_entries(&section_path, &section.entries)?;
+        for component_section in &section.component_sections {
+            let component_path = section_path.join(&component_section.id);
+            ensure_dir(&component_path)?;
+            write_entries(&component_path, &component_section.entries)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_entries(path: &Path, entries: &[Entry]) -> Result<()> {
+    for entry in entries {
+        write_new_file(&path.join(&entry.filename), &entry.file_content()?)?;
+    }
+    Ok(())
+}
+
+fn write_new_file(path: &Path, content: &str) -> Result<()> {
+    if file_exists(path) {
+        return Err(Error::FileExists(path_to_str(path)));
+    }
+    fs::write(path, format!("{content}\n")).map_err(|e| Error::Io(path.to_path_buf(), e))?;
+    debug!("Wrote {}", path_to_str(path));
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::extract_reference_number;
+
+    #[test]
+    fn reference_number_extraction() {
+        let cases = vec![
+            (
+                "- Fix the thing ([\\#123](https://github.com/org/project/issues/123))",
+                Some(123),
+            ),
+            ("- Fix the thing ([#45](https://example.com/45))", Some(45)),
+            ("- Fix the [link](https://example.com)", None),
+            ("- No references at all", None),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(expected, extract_reference_number(s), "for {s}");
+        }
+    }
+}
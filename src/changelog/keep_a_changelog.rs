@@ -0,0 +1,140 @@
+//! Rendering of changelogs in the [Keep a
+//! Changelog](https://keepachangelog.com) format.
+
+use crate::changelog::config::{KeepAChangelogSection, ReleaseDateSource};
+use crate::vcs::{GenericProject, Project};
+use crate::{ChangeSet, Changelog, Config, Release, Result};
+use log::warn;
+use std::collections::BTreeMap;
+
+impl Changelog {
+    /// Renders the full changelog, including unreleased entries, in the [Keep
+    /// a Changelog](https://keepachangelog.com) format.
+    pub fn render_keep_a_changelog_all(&self, config: &Config) -> Result<String> {
+        self.render_keep_a_changelog(config, true)
+    }
+
+    /// Renders all released versions' entries, excluding unreleased ones, in
+    /// the [Keep a Changelog](https://keepachangelog.com) format.
+    pub fn render_keep_a_changelog_released(&self, config: &Config) -> Result<String> {
+        self.render_keep_a_changelog(config, false)
+    }
+
+    fn render_keep_a_changelog(&self, config: &Config, render_unreleased: bool) -> Result<String> {
+        let mut paragraphs = vec![config.heading.clone()];
+        if self.is_empty() {
+            paragraphs.push(config.empty_msg.clone());
+            return Ok(format!("{}\n", paragraphs.join("\n\n")));
+        }
+        if let Some(prologue) = self.prologue.as_ref() {
+            paragraphs.push(prologue.clone());
+        }
+        let maybe_project = config.project().and_then(|project| match project {
+            Ok(project) => Some(project),
+            Err(e) => {
+                warn!("Unable to generate Keep a Changelog links: {e}");
+                None
+            }
+        });
+        let mut links = Vec::new();
+
+        let maybe_unreleased = self
+            .maybe_unreleased
+            .as_ref()
+            .filter(|u| render_unreleased && !u.is_empty());
+        if let Some(unreleased) = maybe_unreleased {
+            paragraphs.push("## [Unreleased]".to_owned());
+            paragraphs.extend(render_change_set(config, unreleased, false)?);
+            if let (Some(project), Some(latest)) = (&maybe_project, self.releases.first()) {
+                push_link(
+                    &mut links,
+                    "unreleased",
+                    project.compare_url(&latest.id, "HEAD"),
+                );
+            }
+        }
+
+        for release in &self.releases {
+            let version = release.version.to_string();
+            paragraphs.push(match release.maybe_date {
+                Some(date) => format!("## [{}] - {}", version, date.format("%F")),
+                None => format!("## [{version}]"),
+            });
+            // Only the summary's first line is a date if that's where we got
+            // the release date from.
+            let summary_has_date = release.maybe_date.is_some()
+                && config.release_date_source == ReleaseDateSource::Summary;
+            paragraphs.extend(render_change_set(
+                config,
+                &release.changes,
+                summary_has_date,
+            )?);
+            if let Some(project) = &maybe_project {
+                let url = release_url(project, release, self.previous_release(release));
+                push_link(&mut links, &version, url);
+            }
+        }
+
+        if let Some(epilogue) = self.epilogue.as_ref() {
+            paragraphs.push(epilogue.clone());
+        }
+        if !links.is_empty() {
+            paragraphs.push(links.join("\n"));
+        }
+        Ok(format!("{}\n", paragraphs.join("\n\n")))
+    }
+}
+
+// Renders the summary (if any) and the sections of the given change set as
+// paragraphs, grouping the change set sections into Keep a Changelog sections.
+fn render_change_set(
+    config: &Config,
+    change_set: &ChangeSet,
+    summary_has_date: bool,
+) -> Result<Vec<String>> {
+    let mut paragraphs = Vec::new();
+    if let Some(summary) = change_set.maybe_summary.as_ref() {
+        // The release date is rendered in the heading, so we don't need to
+        // repeat it here.
+        let summary = if summary_has_date {
+            summary
+                .split_once('\n')
+                .map(|(_, rest)| rest.trim())
+                .unwrap_or_default()
+        } else {
+            summary.as_str()
+        };
+        if !summary.is_empty() {
+            paragraphs.push(summary.to_owned());
+        }
+    }
+    let mut sections: BTreeMap<KeepAChangelogSection, Vec<String>> = BTreeMap::new();
+    for section in change_set.sections.iter().filter(|s| !s.is_empty()) {
+        sections
+            .entry(config.keep_a_changelog.section_for(&section.id))
+            .or_default()
+            .push(section.render_content(config)?);
+    }
+    for (section, content) in sections {
+        paragraphs.push(format!("### {}\n\n{}", section, content.join("\n")));
+    }
+    Ok(paragraphs)
+}
+
+fn release_url(
+    project: &Project,
+    release: &Release,
+    maybe_previous: Option<&Release>,
+) -> Result<url::Url> {
+    match maybe_previous {
+        Some(previous) => project.compare_url(&previous.id, &release.id),
+        None => project.tag_url(&release.id),
+    }
+}
+
+fn push_link(links: &mut Vec<String>, label: &str, maybe_url: Result<url::Url>) {
+    match maybe_url {
+        Ok(url) => links.push(format!("[{label}]: {url}")),
+        Err(e) => warn!("Unable to generate Keep a Changelog link for {label}: {e}"),
+    }
+}
@@ -0,0 +1,236 @@
+//! Linting of changelog entries.
+
+use crate::changelog::config::{LintRule, LintSeverity};
+use crate::changelog::entry::{ids_from_filename, split_front_matter, EntryIdPattern};
+use crate::fs_utils::{entry_filter, read_and_filter_dir, read_to_string};
+use crate::{Changelog, Config, Error, Result};
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A problem found while linting a changelog's entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    /// The path to the file in which the problem was found.
+    pub path: PathBuf,
+    /// The line (starting from 1) on which the problem was found, if the
+    /// problem relates to a specific line.
+    pub maybe_line: Option<usize>,
+    /// The rule that was violated.
+    pub rule: LintRule,
+    /// The configured severity of the rule.
+    pub severity: LintSeverity,
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(line) = self.maybe_line {
+            write!(f, ":{line}")?;
+        }
+        write!(f, ": {}: {} [{}]", self.severity, self.message, self.rule)
+    }
+}
+
+impl Changelog {
+    /// Checks all of the entries in the changelog at the given path for
+    /// problems. The changelog's folders are walked directly instead of
+    /// loading the changelog, so that entries that cannot be loaded (e.g.
+    /// because of their filename or front matter) are reported along with
+    /// everything else. Also reports files in change set sections that would
+    /// be ignored because they do not have the configured entry extension.
+    pub fn lint<P: AsRef<Path>>(config: &Config, path: P) -> Result<Vec<LintIssue>> {
+        let path = path.as_ref();
+        let mut linter = Linter {
+            config,
+            maybe_pattern: config.entry_ids.pattern()?,
+            issues: Vec::new(),
+        };
+        // The unreleased folder and each release's folder contain sections,
+        // which in turn may contain component sections.
+        for change_set_dir in sorted_dirs(path)? {
+            for section_dir in sorted_dirs(&change_set_dir)? {
+                linter.lint_entry_dir(&section_dir)?;
+                for component_dir in sorted_dirs(&section_dir)? {
+                    linter.lint_entry_dir(&component_dir)?;
+                }
+            }
+        }
+        Ok(linter.issues)
+    }
+}
+
+struct Linter<'a> {
+    config: &'a Config,
+    maybe_pattern: Option<EntryIdPattern>,
+    issues: Vec<LintIssue>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, rule: LintRule, path: &Path, maybe_line: Option<usize>, message: String) {
+        let severity = self.config.lint.severity_for(rule);
+        if severity == LintSeverity::Off {
+            return;
+        }
+        self.issues.push(LintIssue {
+            path: path.to_path_buf(),
+            maybe_line,
+            rule,
+            severity,
+            message,
+        });
+    }
+
+    fn lint_entry_file(&mut self, path: &Path) -> Result<()> {
+        let filename = path.file_name().and_then(OsStr::to_str).unwrap_or("");
+        if let Err(e) = ids_from_filename(filename, self.maybe_pattern.as_ref()) {
+            self.report(LintRule::InvalidEntry, path, None, e.to_string());
+        }
+        let content = read_to_string(path)?;
+        self.lint_entry(path, &content);
+        Ok(())
+    }
+
+    fn lint_entry(&mut self, path: &Path, content: &str) {
+        // Front matter is not part of the entry's details, but we still need
+        // to account for it when reporting line numbers.
+        let details = match split_front_matter(content) {
+            Ok((_, details)) => details,
+            Err(e) => {
+                // The front matter starts on the line after its delimiter.
+                let front_matter = content.split_once('\n').map(|(_, rest)| rest);
+                let maybe_line = e
+                    .offset()
+                    .zip(front_matter)
+                    .map(|(offset, front_matter)| front_matter[..offset].matches('\n').count() + 2);
+                self.report(
+                    LintRule::InvalidEntry,
+                    path,
+                    maybe_line,
+                    format!("invalid front matter: {}", e.message()),
+                );
+                // The rest of the entry can't be reliably located.
+                return;
+            }
+        };
+        let first_line = content[..content.len() - details.len()]
+            .matches('\n')
+            .count()
+            + 1;
+        if details.trim().is_empty() {
+            self.report(
+                LintRule::EmptyEntry,
+                path,
+                None,
+                "entry is empty".to_owned(),
+            );
+            return;
+        }
+
+        let bullet = format!("{} ", self.config.bullet_style);
+        let wrap = self.config.wrap as usize;
+        let mut checked_bullet = false;
+        for (i, line) in details.lines().enumerate() {
+            let line_no = Some(first_line + i);
+            if !checked_bullet && !line.trim().is_empty() {
+                checked_bullet = true;
+                if !line.starts_with(&bullet) {
+                    self.report(
+                        LintRule::BulletStyle,
+                        path,
+                        line_no,
+                        format!(
+                            "entry does not start with a \"{}\" bullet",
+                            self.config.bullet_style
+                        ),
+                    );
+                }
+            }
+            let len = line.chars().count();
+            if len > wrap {
+                self.report(
+                    LintRule::LineLength,
+                    path,
+                    line_no,
+                    format!("line is {len} characters long (maximum is {wrap})"),
+                );
+            }
+            if line.ends_with(char::is_whitespace) {
+                self.report(
+                    LintRule::TrailingWhitespace,
+                    path,
+                    line_no,
+                    "line has trailing whitespace".to_owned(),
+                );
+            }
+            if line.contains("<!--") {
+                self.report(
+                    LintRule::HtmlComment,
+                    path,
+                    line_no,
+                    "entry contains an HTML comment (possibly a leftover placeholder)".to_owned(),
+                );
+            }
+        }
+    }
+
+    // Lints the entries in the given directory, and reports files in it that
+    // will be ignored when loading entries because of their extension.
+    fn lint_entry_dir(&mut self, path: &Path) -> Result<()> {
+        let mut entry_files = read_and_filter_dir(path, |e| entry_filter(self.config, e))?;
+        entry_files.sort();
+        for entry_file in entry_files {
+            self.lint_entry_file(&entry_file)?;
+        }
+        let mut ignored_files = read_and_filter_dir(path, |e| ignored_file_filter(self.config, e))?;
+        ignored_files.sort();
+        for file in ignored_files {
+            self.report(
+                LintRule::WrongExtension,
+                &file,
+                None,
+                format!(
+                    "file is ignored because it does not have the \".{}\" extension",
+                    self.config.change_sets.entry_ext
+                ),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn sorted_dirs(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = read_and_filter_dir(path, dir_filter)?;
+    dirs.sort();
+    Ok(dirs)
+}
+
+fn dir_filter(entry: fs::DirEntry) -> Option<Result<PathBuf>> {
+    match entry.metadata() {
+        Ok(meta) if meta.is_dir() => Some(Ok(entry.path())),
+        Ok(_) => None,
+        Err(e) => Some(Err(Error::Io(entry.path(), e))),
+    }
+}
+
+// The inverse of `entry_filter`, but skipping hidden files like `.gitkeep`.
+fn ignored_file_filter(config: &Config, entry: fs::DirEntry) -> Option<Result<PathBuf>> {
+    let meta = match entry.metadata() {
+        Ok(m) => m,
+        Err(e) => return Some(Err(Error::Io(entry.path(), e))),
+    };
+    let path = entry.path();
+    let name = path.file_name()?.to_str()?;
+    let ext = path.extension().and_then(|ext| ext.to_str());
+    if meta.is_file()
+        && !name.starts_with('.')
+        && ext != Some(config.change_sets.entry_ext.as_str())
+    {
+        Some(Ok(path))
+    } else {
+        None
+    }
+}
@@ -3,7 +3,7 @@
 use crate::error::Error;
 
 pub(crate) fn trim_newlines(s: &str) -> &str {
-    s.trim_end_matches(|c| c == '\n' || c == '\r')
+    s.trim_end_matches(['\n', '\r'])
 }
 
 pub(crate) fn extract_release_version(s: &str) -> crate::Result<&str> {
@@ -15,9 +15,26 @@ pub(crate) fn extract_release_version(s: &str) -> crate::Result<&str> {
     Ok(&s[version_start..])
 }
 
+// Converts the given text into a lowercase, dash-separated slug of at most
+// `max_words` words, suitable for use in file and folder names.
+pub(crate) fn slugify(s: &str, max_words: usize) -> String {
+    let slug = s
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(max_words)
+        .collect::<Vec<&str>>()
+        .join("-");
+    if slug.is_empty() {
+        "entry".to_owned()
+    } else {
+        slug
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::extract_release_version;
+    use super::{extract_release_version, slugify};
 
     #[test]
     fn release_version_extraction() {
@@ -34,4 +51,17 @@ mod test {
 
         assert!(extract_release_version("no-version").is_err());
     }
+
+    #[test]
+    fn slug_generation() {
+        let cases = vec![
+            ("Add *new* feature to the CLI", "add-new-feature-to-the-cli"),
+            ("BREAKING CHANGES", "breaking-changes"),
+            ("`--flag` is now 100% faster!", "flag-is-now-100-faster"),
+            ("", "entry"),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(expected, slugify(s, 6));
+        }
+    }
 }
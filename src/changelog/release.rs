@@ -1,13 +1,18 @@
-use crate::changelog::config::SortReleasesBy;
+use crate::changelog::change_set::ChangeSetIter;
+use crate::changelog::config::{ReleaseDateSource, SortReleasesBy, TemplatesConfig};
 use crate::changelog::fs_utils::path_to_str;
 use crate::changelog::parsing_utils::extract_release_version;
+use crate::git::tag_date;
+use crate::vcs::GenericProject;
 use crate::{ChangeSet, Config, Error, Result, Version};
 use chrono::NaiveDate;
 use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::path::Path;
 
 /// The changes associated with a specific release.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Release {
     /// This release's ID (could be the version plus a prefix, e.g. `v0.1.0`).
     pub id: String,
@@ -15,6 +20,7 @@ pub struct Release {
     pub version: Version,
     /// This possibly a release date, parsed according to the configuration file
     /// rules.
+    #[serde(rename = "date")]
     pub maybe_date: Option<NaiveDate>,
     /// The changes associated with this release.
     pub changes: ChangeSet,
@@ -38,27 +44,24 @@ impl Release {
             .to_string_lossy()
             .to_string();
         let version = Version::parse(extract_release_version(&id)?)?;
-        let changes = ChangeSet::read_from_dir(config, path)?;
-        let maybe_date = changes.maybe_summary.as_ref().and_then(|summary| {
-            let summary_first_line = match summary.split('\n').next() {
-                Some(s) => s,
-                None => {
-                    if config.sort_releases_by.0.contains(&SortReleasesBy::Date) {
-                        warn!("Unable to extract release date from {version}: unable to extract first line of summary");
-                    }
-                    return None
+        let changes = ChangeSet::read_from_dir(config, &path)?;
+        let maybe_date = match config.release_date_source {
+            ReleaseDateSource::Summary => changes
+                .maybe_summary
+                .as_ref()
+                .and_then(|summary| date_from_summary(config, &version, summary)),
+            ReleaseDateSource::GitTag => match tag_date(&path, &id) {
+                Ok(Some(date)) => Some(date),
+                Ok(None) => {
+                    warn!("Unable to obtain release date for {version}: no Git tag named \"{id}\"");
+                    None
                 }
-            };
-            for date_fmt in &config.release_date_formats.0 {
-                if let Ok(date) = NaiveDate::parse_from_str(summary_first_line, date_fmt) {
-                    return Some(date);
+                Err(e) => {
+                    warn!("Unable to obtain release date for {version} from Git tag: {e}");
+                    None
                 }
-            }
-            if config.sort_releases_by.0.contains(&SortReleasesBy::Date) {
-                warn!("Unable to parse date from first line of {version}: no formats match \"{summary_first_line}\"");
-            }
-            None
-        });
+            },
+        };
         Ok(Self {
             id,
             version,
@@ -67,13 +70,112 @@ impl Release {
         })
     }
 
+    /// The credits to give all of the authors of this release's entries (see
+    /// [`crate::Entry::credits`]), in alphabetical order.
+    pub fn contributors(&self, config: &Config) -> Vec<String> {
+        let mut contributors = ChangeSetIter::new(&self.changes)
+            .into_iter()
+            .flatten()
+            .flat_map(|entry_path| entry_path.entry().credits(config))
+            .collect::<Vec<_>>();
+        contributors.sort_by_key(|c| c.to_lowercase());
+        contributors.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        contributors
+    }
+
     /// Attempt to render this release to a string using the given
     /// configuration.
-    pub fn render(&self, config: &Config) -> String {
-        let mut paragraphs = vec![format!("## {}", self.id)];
-        if !self.changes.is_empty() {
-            paragraphs.push(self.changes.render(config));
+    pub fn render(&self, config: &Config) -> Result<String> {
+        self.render_with_previous(config, None)
+    }
+
+    /// Attempt to render this release to a string using the given
+    /// configuration, given the release that came before it (if any). The
+    /// previous release is needed in order to link to a comparison between
+    /// the two releases.
+    pub fn render_with_previous(
+        &self,
+        config: &Config,
+        maybe_previous: Option<&Release>,
+    ) -> Result<String> {
+        // Only templates can make use of the compare URL.
+        let templated = config
+            .templates
+            .template(TemplatesConfig::RELEASE_HEADING)
+            .is_some()
+            || config
+                .templates
+                .template(TemplatesConfig::RELEASE)
+                .is_some();
+        let maybe_compare_url = match (maybe_previous, config.project()) {
+            (Some(previous), Some(project)) if templated => {
+                match project.and_then(|p| p.compare_url(&previous.id, &self.id)) {
+                    Ok(url) => Some(url.to_string()),
+                    Err(e) => {
+                        warn!("Unable to construct compare URL for {}: {e}", self.id);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let heading_data = json!({
+            "previous_id": maybe_previous.map(|p| p.id.clone()),
+            "compare_url": maybe_compare_url,
+        });
+        let heading = config
+            .templates
+            .render(TemplatesConfig::RELEASE_HEADING, self, heading_data)?
+            .unwrap_or_else(|| format!("## {}", self.id));
+        let content = if self.changes.is_empty() {
+            String::new()
+        } else {
+            self.changes.render(config)?
+        };
+        let contributors = self.contributors(config);
+        if let Some(rendered) = config.templates.render(
+            TemplatesConfig::RELEASE,
+            self,
+            json!({
+                "heading": heading,
+                "previous_id": maybe_previous.map(|p| p.id.clone()),
+                "compare_url": maybe_compare_url,
+                "content": content,
+                "contributors": contributors,
+            }),
+        )? {
+            return Ok(rendered);
+        }
+        let mut paragraphs = vec![heading];
+        if !content.is_empty() {
+            paragraphs.push(content);
+        }
+        if config.authors.contributors && !contributors.is_empty() {
+            paragraphs.push(format!("Contributors: {}", contributors.join(", ")));
+        }
+        Ok(paragraphs.join("\n\n"))
+    }
+}
+
+// Attempts to parse the first line of the given release summary as a date,
+// according to the configured release date formats.
+fn date_from_summary(config: &Config, version: &Version, summary: &str) -> Option<NaiveDate> {
+    let summary_first_line = match summary.split('\n').next() {
+        Some(s) => s,
+        None => {
+            if config.sort_releases_by.0.contains(&SortReleasesBy::Date) {
+                warn!("Unable to extract release date from {version}: unable to extract first line of summary");
+            }
+            return None;
+        }
+    };
+    for date_fmt in &config.release_date_formats.0 {
+        if let Ok(date) = NaiveDate::parse_from_str(summary_first_line, date_fmt) {
+            return Some(date);
         }
-        paragraphs.join("\n\n")
     }
+    if config.sort_releases_by.0.contains(&SortReleasesBy::Date) {
+        warn!("Unable to parse date from first line of {version}: no formats match \"{summary_first_line}\"");
+    }
+    None
 }
@@ -0,0 +1,86 @@
+//! Scanning of a project's Git history for changes without entries.
+
+use crate::changelog::parsing_utils::slugify;
+use crate::git::{merged_pull_requests, MergedPullRequest};
+use crate::{Changelog, Config, Error, PlatformId, Result};
+use log::info;
+use std::path::Path;
+
+impl Changelog {
+    /// Finds the pull requests merged since the given revision (e.g. the tag
+    /// of the last release) in the Git repository containing the changelog at
+    /// the given path, that are not referenced by any of this changelog's
+    /// entries.
+    ///
+    /// Entries whose IDs are numbers are treated as referencing pull requests
+    /// with the same number, since issue and pull request numbers usually
+    /// share a sequence. Only the first number in an entry's filename counts,
+    /// along with the issues and pull requests listed in its front matter,
+    /// since further numbers in a filename may just be part of its slug (e.g.
+    /// `100-2021-roadmap.md`).
+    pub fn find_pull_requests_without_entries<P: AsRef<Path>>(
+        &self,
+        path: P,
+        maybe_since: Option<&str>,
+    ) -> Result<Vec<MergedPullRequest>> {
+        let referenced = self
+            .entries()
+            .flat_map(|entry_path| {
+                let entry = entry_path.entry();
+                let metadata = &entry.metadata;
+                entry
+                    .platform_ids
+                    .iter()
+                    .filter_map(|platform_id| match platform_id {
+                        PlatformId::Issue(n) | PlatformId::PullRequest(n) => Some(*n),
+                        _ => None,
+                    })
+                    .filter(move |n| {
+                        u64::from(*n) == entry.id
+                            || metadata.issues.contains(n)
+                            || metadata.pull_requests.contains(n)
+                    })
+            })
+            .collect::<Vec<u32>>();
+        Ok(merged_pull_requests(path.as_ref(), maybe_since)?
+            .into_iter()
+            .filter(|pr| !referenced.contains(&pr.number))
+            .collect())
+    }
+
+    /// Adds a placeholder unreleased entry for each of the given pull
+    /// requests to the given section, using the pull request's title as the
+    /// entry's message. See
+    /// [`Changelog::add_unreleased_entry_from_template`].
+    ///
+    /// Fails before adding any entries if the section is not defined, or if
+    /// an entry ID pattern is configured (see [`crate::EntryIdsConfig`]), since
+    /// pull requests can't be mapped to the IDs of an external issue tracker.
+    pub fn add_pull_request_entries<P: AsRef<Path>>(
+        config: &Config,
+        path: P,
+        section: &str,
+        pull_requests: &[MergedPullRequest],
+    ) -> Result<()> {
+        let path = path.as_ref();
+        if !config.change_set_sections.is_defined(section) {
+            return Err(Error::SectionNotDefined(section.to_owned()));
+        }
+        if config.entry_ids.pattern()?.is_some() {
+            return Err(Error::PullRequestEntriesWithEntryIdPattern);
+        }
+        for pr in pull_requests {
+            Self::add_unreleased_entry_from_template(
+                config,
+                path,
+                section,
+                None,
+                &slugify(&pr.title, 6),
+                &[PlatformId::PullRequest(pr.number)],
+                &pr.title,
+            )?;
+            info!("Added placeholder entry for pull request #{}", pr.number);
+        }
+        Ok(())
+    }
+}
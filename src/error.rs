@@ -26,15 +26,37 @@ pub enum Error {
     InvalidSemanticVersion(#[from] semver::Error),
     #[error("expected entry ID to start with a number, but got: \"{0}\"")]
     InvalidEntryId(String),
+    #[error("entry ID \"{0}\" must start with an issue or pull request number, unless an entry ID pattern is configured")]
+    EntryIdWithoutNumber(String),
+    #[error("expected entry ID to match the configured pattern \"{1}\", but got: \"{0}\"")]
+    EntryIdPatternMismatch(String, String),
+    #[error("invalid entry ID pattern: {0}")]
+    InvalidEntryIdPattern(String),
+    #[error("invalid branch pattern: {0}")]
+    InvalidBranchPattern(String),
+    #[error("missing entry ID (--id), and it could not be inferred from the current Git branch")]
+    MissingEntryId,
+    #[error("no paths to commit")]
+    NothingToCommit,
+    #[error("refusing to commit, since changes to other files are already staged: {0}")]
+    UnrelatedStagedChanges(String),
+    #[error("cannot commit to a bare Git repository")]
+    BareGitRepository,
+    #[error("invalid front matter in entry file \"{0}\": {1}")]
+    InvalidEntryFrontMatter(String, #[source] Box<FrontMatterError>),
     #[error("failed to parse entry ID as a number")]
     InvalidEntryNumber(#[from] std::num::ParseIntError),
     #[error("no unreleased entries yet")]
     NoUnreleasedEntries,
+    #[error("no such release: {0}")]
+    NoSuchRelease(String),
+    #[error("no releases in the specified range")]
+    NoReleasesInRange,
     #[error("non-UTF8 characters in string")]
     NonUtf8String(#[from] std::string::FromUtf8Error),
     #[error("non-zero process exit code when executing {0}: {1}")]
     NonZeroExitCode(String, i32),
-    #[error("failed to parse JSON: {0}")]
+    #[error("failed to parse or serialize JSON: {0}")]
     JsonParsingFailed(#[from] serde_json::Error),
     #[error("no such cargo package: {0}")]
     NoSuchCargoPackage(String),
@@ -52,10 +74,14 @@ pub enum Error {
     TomlSerialize(toml::ser::Error),
     #[error("failed to parse URL: {0}")]
     FailedToParseUrl(#[from] url::ParseError),
-    #[error("missing issue number (--issue-no) or pull request (--pull-request)")]
+    #[error(
+        "missing issue number (--issue-no), pull request (--pull-request), ticket (--ticket) or commit (--commit)"
+    )]
     MissingIssueNoOrPullRequest,
-    #[error("please specify either an issue number (--issue-no) or a pull request (--pull-request), but not both")]
-    EitherIssueNoOrPullRequest,
+    #[error(
+        "no URL configured for ticket \"{0}\" (see the change_urls.ticket configuration option)"
+    )]
+    MissingTicketUrl(String),
     #[error("the URL is missing its host: {0}")]
     UrlMissingHost(String),
     #[error("not a GitHub project: {0}")]
@@ -64,12 +90,26 @@ pub enum Error {
     GitHubProjectMissingPath(String),
     #[error("GitHub project URLs must include both the org/user ID and project ID: {0}")]
     InvalidGitHubProjectPath(String),
+    #[error("not a Gitea project: {0}")]
+    NotGiteaProject(String),
+    #[error("not a Bitbucket project: {0}")]
+    NotBitbucketProject(String),
+    #[error("Bitbucket Server/Data Center projects are not supported: {0}")]
+    UnsupportedBitbucketServerProject(String),
+    #[error("not an Azure DevOps project: {0}")]
+    NotAzureDevOpsProject(String),
+    #[error("project URLs must include both the owner and project ID: {0}")]
+    InvalidProjectPath(String),
+    #[error("unrecognized platform: {0} (expected \"github\", \"gitlab\", \"gitea\", \"bitbucket\" or \"azure-devops\")")]
+    UnrecognizedPlatform(String),
     #[error("configuration is missing a project URL (needed for automatic entry generation)")]
     MissingProjectUrl,
     #[error("error loading Handlebars template: {0}")]
     HandlebarsTemplateLoad(String),
     #[error("error rendering Handlebars template: {0}")]
     HandlebarsTemplateRender(String),
+    #[error("no such template: {0}")]
+    UnknownTemplate(String),
     #[error("git error: {0}")]
     Git(#[from] git2::Error),
     #[error("configuration file already exists: {0}")]
@@ -82,6 +122,45 @@ pub enum Error {
     InvalidUrl(String),
     #[error("component \"{0}\" is not defined in changelog config.toml file")]
     ComponentNotDefined(String),
+    #[error("change set section \"{0}\" is not defined in changelog config.toml file")]
+    SectionNotDefined(String),
+    #[error("cannot create entries for pull requests when an entry ID pattern is configured")]
+    PullRequestEntriesWithEntryIdPattern,
     #[error("CLI error: {0}")]
     CommandLine(String),
+    #[error("{0} is out of date with the changelog")]
+    ChangelogOutOfDate(String, String),
+    #[error("linting found {0} error(s)")]
+    LintFailed(usize),
+    #[error("{0} entry file(s) are not formatted")]
+    EntriesNotFormatted(usize),
+}
+
+/// An error parsing the front matter of an entry file.
+#[derive(Debug, Error)]
+pub enum FrontMatterError {
+    #[error("{0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("{0}")]
+    Yaml(#[from] serde_yaml_ng::Error),
+}
+
+impl FrontMatterError {
+    /// The byte offset within the front matter at which the error occurred,
+    /// if known.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Self::Toml(e) => e.span().map(|span| span.start),
+            Self::Yaml(e) => e.location().map(|location| location.index()),
+        }
+    }
+
+    /// The error message, without any location information (where
+    /// possible).
+    pub fn message(&self) -> String {
+        match self {
+            Self::Toml(e) => e.message().to_owned(),
+            Self::Yaml(e) => e.to_string(),
+        }
+    }
 }
@@ -0,0 +1,433 @@
+//! Git-related functionality, e.g. for inspecting a project's history.
+
+use crate::fs_utils::path_to_str;
+use crate::{Error, PlatformId, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use git2::{Delta, DiffFindOptions, DiffOptions, IndexAddOption, Oid, Repository, Sort};
+use log::{debug, info};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const MERGE_COMMIT_PREFIX: &str = "Merge pull request #";
+const GITHUB_NOREPLY_SUFFIX: &str = "@users.noreply.github.com";
+
+/// A pull request that was merged into a project, as identified from the
+/// message of its merge or squash commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedPullRequest {
+    /// The number of the pull request.
+    pub number: u32,
+    /// The title of the pull request.
+    pub title: String,
+    /// The ID of the commit that merged the pull request.
+    pub commit: String,
+}
+
+/// The author of a Git commit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Author {
+    /// The author's name.
+    pub name: String,
+    /// The author's email address.
+    pub email: String,
+}
+
+impl Author {
+    /// The author's GitHub handle, if their email address is a GitHub
+    /// `noreply` address (e.g. `12345+alice@users.noreply.github.com`).
+    pub fn github_handle(&self) -> Option<&str> {
+        let user = self.email.strip_suffix(GITHUB_NOREPLY_SUFFIX)?;
+        let handle = user.split_once('+').map_or(user, |(_, handle)| handle);
+        Some(handle).filter(|h| !h.is_empty())
+    }
+}
+
+impl From<git2::Signature<'_>> for Author {
+    fn from(signature: git2::Signature<'_>) -> Self {
+        Self {
+            name: String::from_utf8_lossy(signature.name_bytes()).into_owned(),
+            email: String::from_utf8_lossy(signature.email_bytes()).into_owned(),
+        }
+    }
+}
+
+/// Opens the Git repository containing the given path.
+pub(crate) fn open_repo(path: &Path) -> Result<Repository> {
+    debug!(
+        "Searching for Git repository containing: {}",
+        path_to_str(path)
+    );
+    Ok(Repository::discover(path)?)
+}
+
+/// Returns the name of the currently checked out branch of the Git repository
+/// containing the given path, or `None` if no branch is checked out (e.g. if
+/// the `HEAD` is detached).
+pub fn current_branch(path: &Path) -> Result<Option<String>> {
+    let repo = open_repo(path)?;
+    let head = match repo.head() {
+        Ok(head) => head,
+        // The branch has no commits yet.
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+            return Ok(repo
+                .find_reference("HEAD")?
+                .symbolic_target()
+                .and_then(|target| target.strip_prefix("refs/heads/"))
+                .map(String::from));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if !head.is_branch() {
+        return Ok(None);
+    }
+    Ok(head.shorthand().map(String::from))
+}
+
+/// Stages all changes (including deletions) to the given paths in the Git
+/// repository containing the first of them, and commits them to the currently
+/// checked out branch with the given message, using the repository's
+/// configured author. Fails if changes to any other paths are already staged,
+/// since they would otherwise be included in the commit.
+pub(crate) fn commit_paths(paths: &[PathBuf], message: &str) -> Result<Oid> {
+    let repo = open_repo(paths.first().ok_or(Error::NothingToCommit)?)?;
+    let pathspecs = paths
+        .iter()
+        .map(|path| relative_to_workdir(&repo, path))
+        .collect::<Result<Vec<PathBuf>>>()?;
+    let maybe_parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit()?),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e.into()),
+    };
+    let mut index = repo.index()?;
+    let parent_tree = maybe_parent.as_ref().map(|c| c.tree()).transpose()?;
+    let staged = repo.diff_tree_to_index(parent_tree.as_ref(), Some(&index), None)?;
+    let unrelated = staged
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+        .filter(|path| !pathspecs.iter().any(|spec| path.starts_with(spec)))
+        .map(path_to_str)
+        .collect::<Vec<String>>();
+    if !unrelated.is_empty() {
+        return Err(Error::UnrelatedStagedChanges(unrelated.join(", ")));
+    }
+    let pathspecs = pathspecs.iter().map(path_to_str).collect::<Vec<String>>();
+    debug!("Staging changes to: {}", pathspecs.join(", "));
+    index.add_all(&pathspecs, IndexAddOption::DEFAULT, None)?;
+    index.update_all(&pathspecs, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = repo.signature()?;
+    let parents = maybe_parent.iter().collect::<Vec<_>>();
+    let oid = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )?;
+    info!("Created commit {oid}");
+    Ok(oid)
+}
+
+/// Finds the author of the commit that added each file currently under the
+/// given path in the history of the checked out branch, following renames.
+/// The returned map is keyed by each file's path relative to the given path.
+pub(crate) fn file_authors(path: &Path) -> Result<HashMap<PathBuf, Author>> {
+    let repo = open_repo(path)?;
+    let prefix = relative_to_workdir(&repo, path)?;
+    let mut diff_opts = DiffOptions::new();
+    if !prefix.as_os_str().is_empty() {
+        diff_opts.pathspec(&prefix);
+    }
+    let mut revwalk = repo.revwalk()?;
+    // Parents must be visited before their children to know which commit
+    // added each file first.
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    revwalk.push_head()?;
+    let mut authors: HashMap<PathBuf, Author> = HashMap::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        // Files only brought in by merge commits were added by the commits
+        // being merged.
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let maybe_parent_tree = match commit.parent_count() {
+            0 => None,
+            _ => Some(commit.parent(0)?.tree()?),
+        };
+        let mut diff = repo.diff_tree_to_tree(
+            maybe_parent_tree.as_ref(),
+            Some(&commit.tree()?),
+            Some(&mut diff_opts),
+        )?;
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+        let author = Author::from(commit.author());
+        for delta in diff.deltas() {
+            let maybe_old = delta.old_file().path();
+            let maybe_new = delta.new_file().path();
+            match (delta.status(), maybe_old, maybe_new) {
+                (Delta::Added | Delta::Copied, _, Some(new)) => {
+                    authors
+                        .entry(new.to_path_buf())
+                        .or_insert_with(|| author.clone());
+                }
+                (Delta::Renamed, Some(old), Some(new)) => {
+                    let original = authors.remove(old).unwrap_or_else(|| author.clone());
+                    authors.insert(new.to_path_buf(), original);
+                }
+                (Delta::Deleted, Some(old), _) => {
+                    authors.remove(old);
+                }
+                _ => (),
+            }
+        }
+    }
+    Ok(authors
+        .into_iter()
+        .filter_map(|(file, author)| Some((file.strip_prefix(&prefix).ok()?.to_path_buf(), author)))
+        .collect())
+}
+
+// Returns the given path relative to the working directory of the given
+// repository.
+fn relative_to_workdir(repo: &Repository, path: &Path) -> Result<PathBuf> {
+    let workdir = repo
+        .workdir()
+        .ok_or(Error::BareGitRepository)?
+        .canonicalize()
+        .map_err(|e| Error::Io(PathBuf::from("."), e))?;
+    let path = path
+        .canonicalize()
+        .map_err(|e| Error::Io(path.to_path_buf(), e))?;
+    Ok(path.strip_prefix(&workdir)?.to_path_buf())
+}
+
+/// Creates an annotated tag with the given name and message, pointing at the
+/// `HEAD` of the Git repository containing the given path.
+pub(crate) fn create_annotated_tag(path: &Path, name: &str, message: &str) -> Result<Oid> {
+    let repo = open_repo(path)?;
+    let head = repo.head()?.peel(git2::ObjectType::Commit)?;
+    let oid = repo.tag(name, &head, &repo.signature()?, message, false)?;
+    info!("Created tag {name}");
+    Ok(oid)
+}
+
+/// Returns the date of the tag with the given name in the Git repository
+/// containing the given path: the tagger's date for annotated tags, or
+/// otherwise the date of the tagged commit. Returns `None` if there is no such
+/// tag.
+pub(crate) fn tag_date(path: &Path, name: &str) -> Result<Option<NaiveDate>> {
+    let repo = open_repo(path)?;
+    let reference = match repo.find_reference(&format!("refs/tags/{name}")) {
+        Ok(reference) => reference,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let maybe_tagger_time = reference
+        .peel_to_tag()
+        .ok()
+        .and_then(|tag| tag.tagger().map(|tagger| tagger.when()));
+    let time = match maybe_tagger_time {
+        Some(time) => time,
+        None => reference.peel_to_commit()?.time(),
+    };
+    Ok(to_date(time))
+}
+
+// Converts the given Git time to a date in its own time zone.
+fn to_date(time: git2::Time) -> Option<NaiveDate> {
+    let offset = FixedOffset::east_opt(time.offset_minutes() * 60)?;
+    let date_time = DateTime::from_timestamp(time.seconds(), 0)?;
+    Some(date_time.with_timezone(&offset).date_naive())
+}
+
+/// A compiled pattern for extracting entry IDs and the changes to which they
+/// relate from branch names. See [`crate::BranchesConfig`].
+#[derive(Debug, Clone)]
+pub(crate) struct BranchPattern {
+    regex: Regex,
+}
+
+impl BranchPattern {
+    pub(crate) fn new(pattern: &str) -> Result<Self> {
+        // The pattern must always match the whole branch name.
+        let regex = Regex::new(&format!("^(?:{pattern})$"))
+            .map_err(|e| Error::InvalidBranchPattern(e.to_string()))?;
+        Ok(Self { regex })
+    }
+
+    /// Extracts the entry ID, and the platform ID of the change to which it
+    /// relates (if any), from the given branch name. Returns `None` if the
+    /// branch name doesn't match the pattern.
+    pub(crate) fn extract(&self, branch: &str) -> Option<(String, Option<PlatformId>)> {
+        let captures = self.regex.captures(branch)?;
+        let id = captures.name("id").or_else(|| captures.get(0))?.as_str();
+        let number = |name| captures.name(name)?.as_str().parse().ok();
+        let maybe_platform_id = number("issue")
+            .map(PlatformId::Issue)
+            .or_else(|| number("pull_request").map(PlatformId::PullRequest))
+            .or_else(|| {
+                captures
+                    .name("ticket")
+                    .map(|m| PlatformId::Ticket(m.as_str().to_owned()))
+            });
+        Some((id.to_owned(), maybe_platform_id))
+    }
+}
+
+/// Finds all pull requests merged into the currently checked out branch of the
+/// Git repository containing the given path, from newest to oldest. If a
+/// revision (e.g. a tag) is given, only pull requests merged since that
+/// revision are returned.
+///
+/// Pull requests are identified from commit subjects of the form
+/// `Merge pull request #N ...` (merge commits) or `Some title (#N)` (squash
+/// commits).
+pub fn merged_pull_requests(
+    path: &Path,
+    maybe_since: Option<&str>,
+) -> Result<Vec<MergedPullRequest>> {
+    let repo = open_repo(path)?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push_head()?;
+    if let Some(since) = maybe_since {
+        let since_commit = repo.revparse_single(since)?.peel_to_commit()?;
+        debug!("Scanning commits since {since} ({})", since_commit.id());
+        revwalk.hide(since_commit.id())?;
+    }
+    let mut seen = HashSet::new();
+    let mut pull_requests = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let Some((number, title)) = commit.message().and_then(parse_pull_request) else {
+            continue;
+        };
+        if seen.insert(number) {
+            pull_requests.push(MergedPullRequest {
+                number,
+                title,
+                commit: commit.id().to_string(),
+            });
+        }
+    }
+    Ok(pull_requests)
+}
+
+// Extracts the number and title of the pull request merged by the commit with
+// the given message, if any.
+fn parse_pull_request(message: &str) -> Option<(u32, String)> {
+    let mut lines = message.lines();
+    let subject = lines.next()?.trim();
+    if let Some(rest) = subject.strip_prefix(MERGE_COMMIT_PREFIX) {
+        let number = rest.split_whitespace().next()?.parse().ok()?;
+        // The title of the pull request is in the body of merge commits.
+        let title = lines
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or(subject);
+        return Some((number, title.to_owned()));
+    }
+    let (title, rest) = subject.strip_suffix(')')?.rsplit_once("(#")?;
+    let number = rest.parse().ok()?;
+    Some((number, title.trim().to_owned()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_pull_request, Author, BranchPattern};
+    use crate::{BranchesConfig, PlatformId};
+
+    #[test]
+    fn branch_name_extraction() {
+        let pattern = BranchesConfig::default().pattern().unwrap();
+        let cases = vec![
+            (
+                "1234-fix-timeouts",
+                Some(("1234-fix-timeouts", Some(PlatformId::Issue(1234)))),
+            ),
+            (
+                "someone/56-add-feature",
+                Some(("56-add-feature", Some(PlatformId::Issue(56)))),
+            ),
+            ("main", None),
+            ("fix-1234", None),
+        ];
+        for (branch, expected) in cases {
+            assert_eq!(
+                expected.map(|(id, platform_id)| (id.to_owned(), platform_id)),
+                pattern.extract(branch),
+                "for {branch}"
+            );
+        }
+
+        let pattern = BranchPattern::new(r"feature/(?P<id>(?P<ticket>[A-Z]+-[0-9]+)-.+)").unwrap();
+        assert_eq!(
+            pattern.extract("feature/PROJ-12-do-things"),
+            Some((
+                "PROJ-12-do-things".to_owned(),
+                Some(PlatformId::Ticket("PROJ-12".to_owned()))
+            ))
+        );
+        assert_eq!(
+            BranchPattern::new(r"(?P<pull_request>[0-9]+)")
+                .unwrap()
+                .extract("42"),
+            Some(("42".to_owned(), Some(PlatformId::PullRequest(42))))
+        );
+        assert!(BranchPattern::new("(unclosed").is_err());
+    }
+
+    #[test]
+    fn github_handle_from_email() {
+        let cases = [
+            ("12345+alice@users.noreply.github.com", Some("alice")),
+            ("bob@users.noreply.github.com", Some("bob")),
+            ("carol@example.com", None),
+            ("12345+@users.noreply.github.com", None),
+        ];
+        for (email, expected) in cases {
+            let author = Author {
+                name: "Someone".to_owned(),
+                email: email.to_owned(),
+            };
+            assert_eq!(author.github_handle(), expected, "for {email}");
+        }
+    }
+
+    #[test]
+    fn pull_request_parsing() {
+        let cases = vec![
+            (
+                "Merge pull request #12 from org/branch\n\nAdd a feature",
+                Some((12, "Add a feature")),
+            ),
+            (
+                "Merge pull request #12 from org/branch",
+                Some((12, "Merge pull request #12 from org/branch")),
+            ),
+            ("Fix a bug (#34)", Some((34, "Fix a bug"))),
+            (
+                "Fix a bug (#34)\n\n* Some commit (#33)",
+                Some((34, "Fix a bug")),
+            ),
+            ("Fix (#2) properly (#34)", Some((34, "Fix (#2) properly"))),
+            ("Fix a bug", None),
+            ("Fix a bug (#abc)", None),
+            ("Merge branch 'main' into feature", None),
+        ];
+        for (message, expected) in cases {
+            let actual = parse_pull_request(message);
+            assert_eq!(
+                expected.map(|(n, t)| (n, t.to_owned())),
+                actual,
+                "for {message}"
+            );
+        }
+    }
+}
@@ -3,18 +3,24 @@
 mod changelog;
 mod error;
 pub mod fs_utils;
+mod git;
 mod s11n;
 mod vcs;
 
 pub use changelog::config::{
-    BulletStyle, ChangeSetsConfig, ComponentsConfig, Config, UnreleasedConfig,
+    AuthorsConfig, BranchesConfig, BulletStyle, ChangeSetSectionConfig, ChangeSetSectionsConfig,
+    ChangeSetsConfig, ChangeUrlsConfig, ComponentsConfig, Config, EntryIdsConfig,
+    KeepAChangelogConfig, KeepAChangelogSection, LintConfig, LintRule, LintSeverity, ReleaseConfig,
+    ReleaseDateSource, ScanConfig, TemplatesConfig, UnreleasedConfig,
 };
 pub use changelog::{
-    ChangeSet, ChangeSetComponentPath, ChangeSetSection, ChangeSetSectionPath, Changelog,
-    Component, ComponentSection, Entry, EntryChangeSetPath, EntryPath, EntryReleasePath, Release,
+    check_changelog_file, ChangeSet, ChangeSetComponentPath, ChangeSetSection,
+    ChangeSetSectionPath, Changelog, Component, ComponentSection, Entry, EntryChangeSetPath,
+    EntryMetadata, EntryPath, EntryReleasePath, LintIssue, Release,
 };
-pub use error::Error;
-pub use vcs::{GenericProject, PlatformId, Project};
+pub use error::{Error, FrontMatterError};
+pub use git::{current_branch, merged_pull_requests, Author, MergedPullRequest};
+pub use vcs::{GenericProject, Platform, PlatformId, Project};
 
 /// Result type used throughout the `unclog` crate.
 pub type Result<T> = std::result::Result<T, Error>;
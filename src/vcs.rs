@@ -3,31 +3,53 @@
 
 use crate::{fs_utils::path_to_str, Error, Result};
 use log::{debug, info};
+use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, path::Path, str::FromStr};
 use url::Url;
 
 /// Provides a way of referencing a change through the VCS platform.
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PlatformId {
     /// The change is referenced by way of issue number.
     Issue(u32),
     /// The change is referenced by way of pull request number.
     PullRequest(u32),
+    /// The change is referenced by way of a ticket ID in an external issue
+    /// tracker (e.g. "PROJ-1234" in Jira).
+    Ticket(String),
+    /// The change is referenced by way of a commit hash.
+    Commit(String),
 }
 
 impl PlatformId {
-    /// Return the integer ID associated with this platform-specific ID.
-    pub fn id(&self) -> u32 {
+    /// Return the ID associated with this platform-specific ID.
+    pub fn id(&self) -> String {
         match self {
-            Self::Issue(issue) => *issue,
-            Self::PullRequest(pull_request) => *pull_request,
+            Self::Issue(issue) => issue.to_string(),
+            Self::PullRequest(pull_request) => pull_request.to_string(),
+            Self::Ticket(ticket) => ticket.clone(),
+            Self::Commit(commit) => commit.clone(),
+        }
+    }
+
+    /// Return the kind of reference this is (i.e. "issue", "pull_request",
+    /// "ticket" or "commit").
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Issue(_) => "issue",
+            Self::PullRequest(_) => "pull_request",
+            Self::Ticket(_) => "ticket",
+            Self::Commit(_) => "commit",
         }
     }
 }
 
 /// Generic definition of an online Git project.
 pub trait GenericProject {
-    fn change_url(&self, platform_id: PlatformId) -> Result<Url>;
+    fn change_url(&self, platform_id: &PlatformId) -> Result<Url>;
+    fn compare_url(&self, from: &str, to: &str) -> Result<Url>;
+    fn tag_url(&self, tag: &str) -> Result<Url>;
     fn url_str(&self) -> String;
     fn url(&self) -> Url;
 }
@@ -38,9 +60,11 @@ impl std::fmt::Display for dyn GenericProject {
     }
 }
 
-/// A project on GitHub.
+/// A project on GitHub (including GitHub Enterprise instances).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GitHubProject {
+    /// The host of the project (e.g. "github.com").
+    pub host: String,
     /// The organization or user associated with this project.
     pub owner: String,
     /// The ID of the project.
@@ -59,6 +83,18 @@ impl TryFrom<&Url> for GitHubProject {
             return Err(Error::NotGitHubProject(url.to_string()));
         }
 
+        Self::from_url(url)
+    }
+}
+
+impl GitHubProject {
+    // Parses the given URL as a GitHub project URL without checking its host
+    // (e.g. for GitHub Enterprise instances).
+    fn from_url(url: &Url) -> Result<Self> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| Error::UrlMissingHost(url.to_string()))?;
+
         let path_parts = url
             .path_segments()
             .ok_or_else(|| Error::GitHubProjectMissingPath(url.to_string()))?
@@ -69,6 +105,7 @@ impl TryFrom<&Url> for GitHubProject {
         }
 
         Ok(Self {
+            host: host.to_owned(),
             owner: path_parts[0].to_owned(),
             project: path_parts[1].trim_end_matches(".git").to_owned(),
         })
@@ -93,19 +130,32 @@ impl std::fmt::Display for GitHubProject {
 impl GenericProject for GitHubProject {
     /// Construct a URL for this project based on the given platform-specific
     /// ID.
-    fn change_url(&self, platform_id: PlatformId) -> Result<Url> {
+    fn change_url(&self, platform_id: &PlatformId) -> Result<Url> {
         Ok(Url::parse(&format!(
             "{}/{}",
             self,
             match platform_id {
                 PlatformId::Issue(no) => format!("issues/{no}"),
                 PlatformId::PullRequest(no) => format!("pull/{no}"),
+                PlatformId::Commit(commit) => format!("commit/{commit}"),
+                PlatformId::Ticket(ticket) => return Err(Error::MissingTicketUrl(ticket.clone())),
             }
         ))?)
     }
 
+    /// Construct a URL comparing the two given references (e.g. tags) in this
+    /// project.
+    fn compare_url(&self, from: &str, to: &str) -> Result<Url> {
+        Ok(Url::parse(&format!("{self}/compare/{from}...{to}"))?)
+    }
+
+    /// Construct a URL for the release associated with the given tag.
+    fn tag_url(&self, tag: &str) -> Result<Url> {
+        Ok(Url::parse(&format!("{self}/releases/tag/{tag}"))?)
+    }
+
     fn url_str(&self) -> String {
-        format!("https://github.com/{}/{}", self.owner, self.project)
+        format!("https://{}/{}/{}", self.host, self.owner, self.project)
     }
 
     fn url(&self) -> Url {
@@ -137,6 +187,18 @@ impl TryFrom<&Url> for GitLabProject {
             return Err(Error::NotGitHubProject(url.to_string()));
         }
 
+        Self::from_url(url)
+    }
+}
+
+impl GitLabProject {
+    // Parses the given URL as a GitLab project URL without checking its host
+    // (e.g. for self-hosted instances).
+    fn from_url(url: &Url) -> Result<Self> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| Error::UrlMissingHost(url.to_string()))?;
+
         let mut path_parts = url
             .path_segments()
             .ok_or_else(|| Error::GitHubProjectMissingPath(url.to_string()))?
@@ -178,17 +240,33 @@ impl std::fmt::Display for GitLabProject {
 impl GenericProject for GitLabProject {
     /// Construct a URL for this project based on the given platform-specific
     /// ID.
-    fn change_url(&self, platform_id: PlatformId) -> Result<Url> {
+    fn change_url(&self, platform_id: &PlatformId) -> Result<Url> {
         Ok(Url::parse(&format!(
             "{}/{}",
             self,
             match platform_id {
                 PlatformId::Issue(no) => format!("-/issues/{}", no),
                 PlatformId::PullRequest(no) => format!("-/merge_requests/{}", no),
+                PlatformId::Commit(commit) => format!("-/commit/{}", commit),
+                PlatformId::Ticket(ticket) => return Err(Error::MissingTicketUrl(ticket.clone())),
             }
         ))?)
     }
 
+    /// Construct a URL comparing the two given references (e.g. tags) in this
+    /// project.
+    fn compare_url(&self, from: &str, to: &str) -> Result<Url> {
+        Ok(Url::parse(&format!(
+            "{}/-/compare/{}...{}",
+            self, from, to
+        ))?)
+    }
+
+    /// Construct a URL for the given tag.
+    fn tag_url(&self, tag: &str) -> Result<Url> {
+        Ok(Url::parse(&format!("{}/-/tags/{}", self, tag))?)
+    }
+
     fn url_str(&self) -> String {
         format!("https://{}/{}/{}", self.host, self.root_url, self.project)
     }
@@ -200,16 +278,418 @@ impl GenericProject for GitLabProject {
     }
 }
 
+/// A project on a Gitea instance (including Forgejo instances, like
+/// Codeberg).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GiteaProject {
+    /// The host of the project.
+    pub host: String,
+    /// The organization or user associated with this project.
+    pub owner: String,
+    /// The ID of the project.
+    pub project: String,
+}
+
+impl TryFrom<&Url> for GiteaProject {
+    type Error = Error;
+
+    fn try_from(url: &Url) -> Result<Self> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| Error::UrlMissingHost(url.to_string()))?;
+
+        if !(host.contains("gitea") || host.contains("forgejo") || host == "codeberg.org") {
+            return Err(Error::NotGiteaProject(url.to_string()));
+        }
+
+        Self::from_url(url)
+    }
+}
+
+impl GiteaProject {
+    // Parses the given URL as a Gitea project URL without checking its host
+    // (e.g. for self-hosted instances).
+    fn from_url(url: &Url) -> Result<Self> {
+        let (host, path_parts) = host_and_path_parts(url)?;
+
+        if path_parts.len() < 2 {
+            return Err(Error::InvalidProjectPath(url.to_string()));
+        }
+
+        Ok(Self {
+            host: host.to_owned(),
+            owner: path_parts[0].to_owned(),
+            project: path_parts[1].trim_end_matches(".git").to_owned(),
+        })
+    }
+}
+
+impl FromStr for GiteaProject {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let url = Url::parse(s)?;
+        Self::try_from(&url)
+    }
+}
+
+impl std::fmt::Display for GiteaProject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url_str())
+    }
+}
+
+impl GenericProject for GiteaProject {
+    /// Construct a URL for this project based on the given platform-specific
+    /// ID.
+    fn change_url(&self, platform_id: &PlatformId) -> Result<Url> {
+        Ok(Url::parse(&format!(
+            "{}/{}",
+            self,
+            match platform_id {
+                PlatformId::Issue(no) => format!("issues/{no}"),
+                PlatformId::PullRequest(no) => format!("pulls/{no}"),
+                PlatformId::Commit(commit) => format!("commit/{commit}"),
+                PlatformId::Ticket(ticket) => return Err(Error::MissingTicketUrl(ticket.clone())),
+            }
+        ))?)
+    }
+
+    /// Construct a URL comparing the two given references (e.g. tags) in this
+    /// project.
+    fn compare_url(&self, from: &str, to: &str) -> Result<Url> {
+        Ok(Url::parse(&format!("{self}/compare/{from}...{to}"))?)
+    }
+
+    /// Construct a URL for the release associated with the given tag.
+    fn tag_url(&self, tag: &str) -> Result<Url> {
+        Ok(Url::parse(&format!("{self}/releases/tag/{tag}"))?)
+    }
+
+    fn url_str(&self) -> String {
+        format!("https://{}/{}/{}", self.host, self.owner, self.project)
+    }
+
+    fn url(&self) -> Url {
+        let url_str = self.url_str();
+        Url::parse(&url_str).unwrap_or_else(|e| panic!("failed to parse URL \"{url_str}\": {e}"))
+    }
+}
+
+/// A project on Bitbucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitbucketProject {
+    /// The host of the project.
+    pub host: String,
+    /// The workspace associated with this project.
+    pub workspace: String,
+    /// The ID of the project (i.e. the repository slug).
+    pub project: String,
+}
+
+impl TryFrom<&Url> for BitbucketProject {
+    type Error = Error;
+
+    fn try_from(url: &Url) -> Result<Self> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| Error::UrlMissingHost(url.to_string()))?;
+
+        if !host.contains("bitbucket") {
+            return Err(Error::NotBitbucketProject(url.to_string()));
+        }
+
+        Self::from_url(url)
+    }
+}
+
+impl BitbucketProject {
+    // Parses the given URL as a Bitbucket project URL without checking its
+    // host.
+    fn from_url(url: &Url) -> Result<Self> {
+        let (host, path_parts) = host_and_path_parts(url)?;
+
+        // Bitbucket Server/Data Center repository URLs look like
+        // "https://host/scm/PROJ/repo.git" or "https://host/projects/PROJ/repos/repo",
+        // and use different URLs for changes.
+        if matches!(path_parts.first(), Some(&"scm") | Some(&"projects")) {
+            return Err(Error::UnsupportedBitbucketServerProject(url.to_string()));
+        }
+
+        if path_parts.len() < 2 {
+            return Err(Error::InvalidProjectPath(url.to_string()));
+        }
+
+        Ok(Self {
+            host: host.to_owned(),
+            workspace: path_parts[0].to_owned(),
+            project: path_parts[1].trim_end_matches(".git").to_owned(),
+        })
+    }
+}
+
+impl FromStr for BitbucketProject {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let url = Url::parse(s)?;
+        Self::try_from(&url)
+    }
+}
+
+impl std::fmt::Display for BitbucketProject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url_str())
+    }
+}
+
+impl GenericProject for BitbucketProject {
+    /// Construct a URL for this project based on the given platform-specific
+    /// ID.
+    fn change_url(&self, platform_id: &PlatformId) -> Result<Url> {
+        Ok(Url::parse(&format!(
+            "{}/{}",
+            self,
+            match platform_id {
+                PlatformId::Issue(no) => format!("issues/{no}"),
+                PlatformId::PullRequest(no) => format!("pull-requests/{no}"),
+                PlatformId::Commit(commit) => format!("commits/{commit}"),
+                PlatformId::Ticket(ticket) => return Err(Error::MissingTicketUrl(ticket.clone())),
+            }
+        ))?)
+    }
+
+    /// Construct a URL comparing the two given references (e.g. tags) in this
+    /// project.
+    fn compare_url(&self, from: &str, to: &str) -> Result<Url> {
+        Ok(Url::parse(&format!(
+            "{self}/branches/compare/{to}%0D{from}"
+        ))?)
+    }
+
+    /// Construct a URL for the source at the given tag.
+    fn tag_url(&self, tag: &str) -> Result<Url> {
+        Ok(Url::parse(&format!("{self}/src/{tag}"))?)
+    }
+
+    fn url_str(&self) -> String {
+        format!("https://{}/{}/{}", self.host, self.workspace, self.project)
+    }
+
+    fn url(&self) -> Url {
+        let url_str = self.url_str();
+        Url::parse(&url_str).unwrap_or_else(|e| panic!("failed to parse URL \"{url_str}\": {e}"))
+    }
+}
+
+/// A project (i.e. a Git repository) on Azure DevOps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AzureDevOpsProject {
+    /// The URL of the organization or collection to which the project belongs
+    /// (e.g. "https://dev.azure.com/org").
+    pub organization_url: String,
+    /// The Azure DevOps project.
+    pub project: String,
+    /// The ID of the Git repository within the project.
+    pub repository: String,
+}
+
+impl TryFrom<&Url> for AzureDevOpsProject {
+    type Error = Error;
+
+    fn try_from(url: &Url) -> Result<Self> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| Error::UrlMissingHost(url.to_string()))?;
+
+        if !(host.ends_with("dev.azure.com") || host.ends_with("visualstudio.com")) {
+            return Err(Error::NotAzureDevOpsProject(url.to_string()));
+        }
+
+        Self::from_url(url)
+    }
+}
+
+impl AzureDevOpsProject {
+    // Parses the given URL as an Azure DevOps project URL without checking its
+    // host (e.g. for Azure DevOps Server instances).
+    fn from_url(url: &Url) -> Result<Self> {
+        let (host, path_parts) = host_and_path_parts(url)?;
+
+        // SSH URLs look like "git@ssh.dev.azure.com:v3/org/project/repo".
+        if path_parts.len() == 4 && path_parts[0] == "v3" {
+            return Ok(Self {
+                organization_url: format!("https://dev.azure.com/{}", path_parts[1]),
+                project: path_parts[2].to_owned(),
+                repository: path_parts[3].trim_end_matches(".git").to_owned(),
+            });
+        }
+
+        // HTTPS URLs look like "https://dev.azure.com/org/project/_git/repo"
+        // or "https://org.visualstudio.com/project/_git/repo".
+        let git_pos = path_parts
+            .iter()
+            .position(|part| *part == "_git")
+            .filter(|pos| *pos >= 1 && *pos + 1 < path_parts.len())
+            .ok_or_else(|| Error::InvalidProjectPath(url.to_string()))?;
+        let organization_url = std::iter::once(format!("https://{host}"))
+            .chain(
+                path_parts[..git_pos - 1]
+                    .iter()
+                    .map(|part| part.to_string()),
+            )
+            .collect::<Vec<String>>()
+            .join("/");
+
+        Ok(Self {
+            organization_url,
+            project: path_parts[git_pos - 1].to_owned(),
+            repository: path_parts[git_pos + 1].trim_end_matches(".git").to_owned(),
+        })
+    }
+}
+
+impl FromStr for AzureDevOpsProject {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let url = Url::parse(s)?;
+        Self::try_from(&url)
+    }
+}
+
+impl std::fmt::Display for AzureDevOpsProject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url_str())
+    }
+}
+
+impl GenericProject for AzureDevOpsProject {
+    /// Construct a URL for this project based on the given platform-specific
+    /// ID. Issues are assumed to be work items.
+    fn change_url(&self, platform_id: &PlatformId) -> Result<Url> {
+        Ok(Url::parse(&match platform_id {
+            PlatformId::Issue(no) => format!(
+                "{}/{}/_workitems/edit/{no}",
+                self.organization_url, self.project
+            ),
+            PlatformId::PullRequest(no) => format!("{self}/pullrequest/{no}"),
+            PlatformId::Commit(commit) => format!("{self}/commit/{commit}"),
+            PlatformId::Ticket(ticket) => return Err(Error::MissingTicketUrl(ticket.clone())),
+        })?)
+    }
+
+    /// Construct a URL comparing the two given tags in this project.
+    fn compare_url(&self, from: &str, to: &str) -> Result<Url> {
+        Ok(Url::parse(&format!(
+            "{self}/branchCompare?baseVersion=GT{from}&targetVersion=GT{to}"
+        ))?)
+    }
+
+    /// Construct a URL for the source at the given tag.
+    fn tag_url(&self, tag: &str) -> Result<Url> {
+        Ok(Url::parse(&format!("{self}?version=GT{tag}"))?)
+    }
+
+    fn url_str(&self) -> String {
+        format!(
+            "{}/{}/_git/{}",
+            self.organization_url, self.project, self.repository
+        )
+    }
+
+    fn url(&self) -> Url {
+        let url_str = self.url_str();
+        Url::parse(&url_str).unwrap_or_else(|e| panic!("failed to parse URL \"{url_str}\": {e}"))
+    }
+}
+
+/// The platforms on which projects can be hosted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    /// [GitHub](https://github.com).
+    GitHub,
+    /// [GitLab](https://gitlab.com), including self-hosted instances.
+    GitLab,
+    /// [Gitea](https://about.gitea.com), including
+    /// [Forgejo](https://forgejo.org) instances.
+    #[serde(alias = "forgejo")]
+    Gitea,
+    /// [Bitbucket](https://bitbucket.org).
+    Bitbucket,
+    /// [Azure DevOps](https://dev.azure.com).
+    #[serde(rename = "azure-devops", alias = "azuredevops")]
+    AzureDevOps,
+}
+
+impl std::fmt::Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::GitHub => "github",
+                Self::GitLab => "gitlab",
+                Self::Gitea => "gitea",
+                Self::Bitbucket => "bitbucket",
+                Self::AzureDevOps => "azure-devops",
+            }
+        )
+    }
+}
+
+impl FromStr for Platform {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "github" => Ok(Self::GitHub),
+            "gitlab" => Ok(Self::GitLab),
+            "gitea" | "forgejo" => Ok(Self::Gitea),
+            "bitbucket" => Ok(Self::Bitbucket),
+            "azure-devops" | "azuredevops" => Ok(Self::AzureDevOps),
+            _ => Err(Error::UnrecognizedPlatform(s.to_owned())),
+        }
+    }
+}
+
 pub enum Project {
     GitHubProject(GitHubProject),
     GitLabProject(GitLabProject),
+    GiteaProject(GiteaProject),
+    BitbucketProject(BitbucketProject),
+    AzureDevOpsProject(AzureDevOpsProject),
 }
 
 impl GenericProject for Project {
-    fn change_url(&self, platform_id: PlatformId) -> Result<Url> {
+    fn change_url(&self, platform_id: &PlatformId) -> Result<Url> {
         match self {
             Project::GitHubProject(github) => github.change_url(platform_id),
             Project::GitLabProject(gitlab) => gitlab.change_url(platform_id),
+            Project::GiteaProject(gitea) => gitea.change_url(platform_id),
+            Project::BitbucketProject(bitbucket) => bitbucket.change_url(platform_id),
+            Project::AzureDevOpsProject(azure) => azure.change_url(platform_id),
+        }
+    }
+
+    fn compare_url(&self, from: &str, to: &str) -> Result<Url> {
+        match self {
+            Project::GitHubProject(github) => github.compare_url(from, to),
+            Project::GitLabProject(gitlab) => gitlab.compare_url(from, to),
+            Project::GiteaProject(gitea) => gitea.compare_url(from, to),
+            Project::BitbucketProject(bitbucket) => bitbucket.compare_url(from, to),
+            Project::AzureDevOpsProject(azure) => azure.compare_url(from, to),
+        }
+    }
+
+    fn tag_url(&self, tag: &str) -> Result<Url> {
+        match self {
+            Project::GitHubProject(github) => github.tag_url(tag),
+            Project::GitLabProject(gitlab) => gitlab.tag_url(tag),
+            Project::GiteaProject(gitea) => gitea.tag_url(tag),
+            Project::BitbucketProject(bitbucket) => bitbucket.tag_url(tag),
+            Project::AzureDevOpsProject(azure) => azure.tag_url(tag),
         }
     }
 
@@ -217,6 +697,9 @@ impl GenericProject for Project {
         match self {
             Project::GitHubProject(github) => github.url_str(),
             Project::GitLabProject(gitlab) => gitlab.url_str(),
+            Project::GiteaProject(gitea) => gitea.url_str(),
+            Project::BitbucketProject(bitbucket) => bitbucket.url_str(),
+            Project::AzureDevOpsProject(azure) => azure.url_str(),
         }
     }
 
@@ -224,6 +707,9 @@ impl GenericProject for Project {
         match self {
             Project::GitHubProject(github) => github.url(),
             Project::GitLabProject(gitlab) => gitlab.url(),
+            Project::GiteaProject(gitea) => gitea.url(),
+            Project::BitbucketProject(bitbucket) => bitbucket.url(),
+            Project::AzureDevOpsProject(azure) => azure.url(),
         }
     }
 }
@@ -233,11 +719,21 @@ impl std::fmt::Display for Project {
         match self {
             Project::GitHubProject(github) => github.fmt(f),
             Project::GitLabProject(gitlab) => gitlab.fmt(f),
+            Project::GiteaProject(gitea) => gitea.fmt(f),
+            Project::BitbucketProject(bitbucket) => bitbucket.fmt(f),
+            Project::AzureDevOpsProject(azure) => azure.fmt(f),
         }
     }
 }
 
-pub fn from_git_repo(path: &Path, remote: &str) -> Result<Project> {
+/// Attempts to infer the project from the URL of the given remote in the Git
+/// repository at the given path. If a platform is specified, it is assumed
+/// instead of being inferred from the remote URL's host.
+pub fn from_git_repo(
+    path: &Path,
+    remote: &str,
+    maybe_platform: Option<Platform>,
+) -> Result<Project> {
     debug!("Opening path as Git repository: {}", path_to_str(path));
     let repo = git2::Repository::open(path)?;
     let remote_url = repo
@@ -249,7 +745,24 @@ pub fn from_git_repo(path: &Path, remote: &str) -> Result<Project> {
     let remote_url = parse_url(&remote_url)?;
     debug!("Parsed remote URL as: {}", remote_url.to_string());
 
-    try_from(&remote_url)
+    try_from_platform(&remote_url, maybe_platform)
+}
+
+/// Attempts to construct a project from the given URL on the given platform,
+/// or to infer the platform from the URL's host if none is specified.
+pub fn try_from_platform(url: &Url, maybe_platform: Option<Platform>) -> Result<Project> {
+    match maybe_platform {
+        Some(Platform::GitHub) => Ok(Project::GitHubProject(GitHubProject::from_url(url)?)),
+        Some(Platform::GitLab) => Ok(Project::GitLabProject(GitLabProject::from_url(url)?)),
+        Some(Platform::Gitea) => Ok(Project::GiteaProject(GiteaProject::from_url(url)?)),
+        Some(Platform::Bitbucket) => {
+            Ok(Project::BitbucketProject(BitbucketProject::from_url(url)?))
+        }
+        Some(Platform::AzureDevOps) => Ok(Project::AzureDevOpsProject(
+            AzureDevOpsProject::from_url(url)?,
+        )),
+        None => try_from(url),
+    }
 }
 
 pub fn try_from(url: &Url) -> Result<Project> {
@@ -259,17 +772,41 @@ pub fn try_from(url: &Url) -> Result<Project> {
     } else if let Ok(maybe_gitlab_project) = GitLabProject::try_from(url) {
         info!("Deduced GitLab project!");
         Ok(Project::GitLabProject(maybe_gitlab_project))
+    } else if let Ok(maybe_gitea_project) = GiteaProject::try_from(url) {
+        info!("Deduced Gitea project!");
+        Ok(Project::GiteaProject(maybe_gitea_project))
+    } else if let Ok(maybe_bitbucket_project) = BitbucketProject::try_from(url) {
+        info!("Deduced Bitbucket project!");
+        Ok(Project::BitbucketProject(maybe_bitbucket_project))
+    } else if let Ok(maybe_azure_project) = AzureDevOpsProject::try_from(url) {
+        info!("Deduced Azure DevOps project!");
+        Ok(Project::AzureDevOpsProject(maybe_azure_project))
     } else {
         Err(Error::UnrecognizedProjectType(url.to_string()))
     }
 }
 
+// Splits the given project URL into its host and its non-empty path segments.
+fn host_and_path_parts(url: &Url) -> Result<(&str, Vec<&str>)> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| Error::UrlMissingHost(url.to_string()))?;
+    let path_parts = url
+        .path_segments()
+        .ok_or_else(|| Error::InvalidProjectPath(url.to_string()))?
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>();
+    Ok((host, path_parts))
+}
+
 fn parse_url(u: &str) -> Result<Url> {
-    // Not an SSH URL
-    if u.starts_with("http://") || u.starts_with("https://") {
+    // Fully qualified URLs (e.g. "https://..." or "ssh://...")
+    if u.contains("://") {
         return Ok(Url::parse(u)?);
     }
-    Ok(Url::parse(&format!("ssh://{}", u.replace(':', "/")))?)
+    // SCP-like SSH URLs (e.g. "git@github.com:org/project.git"), where only the
+    // first colon separates the host from the path.
+    Ok(Url::parse(&format!("ssh://{}", u.replacen(':', "/", 1)))?)
 }
 
 #[cfg(test)]
@@ -286,6 +823,7 @@ mod test {
             "ssh://git@github.com/informalsystems/unclog.git",
         ];
         let expected = GitHubProject {
+            host: "github.com".to_owned(),
             owner: "informalsystems".to_owned(),
             project: "unclog".to_owned(),
         };
@@ -298,6 +836,7 @@ mod test {
     #[test]
     fn github_project_url_construction() {
         let project = GitHubProject {
+            host: "github.com".to_owned(),
             owner: "informalsystems".to_owned(),
             project: "unclog".to_owned(),
         };
@@ -307,6 +846,52 @@ mod test {
         )
     }
 
+    #[test]
+    fn github_project_compare_url_construction() {
+        let project = GitHubProject {
+            host: "github.com".to_owned(),
+            owner: "informalsystems".to_owned(),
+            project: "unclog".to_owned(),
+        };
+        assert_eq!(
+            project.compare_url("v0.1.0", "v0.2.0").unwrap().as_str(),
+            "https://github.com/informalsystems/unclog/compare/v0.1.0...v0.2.0"
+        );
+        assert_eq!(
+            project.tag_url("v0.1.0").unwrap().as_str(),
+            "https://github.com/informalsystems/unclog/releases/tag/v0.1.0"
+        )
+    }
+
+    #[test]
+    fn github_enterprise_project() {
+        let url = Url::parse("https://github.corp.example/org/project").unwrap();
+        assert!(GitHubProject::try_from(&url).is_err());
+        let project = match try_from_platform(&url, Some(Platform::GitHub)).unwrap() {
+            Project::GitHubProject(project) => project,
+            _ => panic!("expected a GitHub project"),
+        };
+        assert_eq!(
+            project,
+            GitHubProject {
+                host: "github.corp.example".to_owned(),
+                owner: "org".to_owned(),
+                project: "project".to_owned(),
+            }
+        );
+        assert_eq!(
+            project.change_url(&PlatformId::Issue(1)).unwrap().as_str(),
+            "https://github.corp.example/org/project/issues/1"
+        );
+        assert_eq!(
+            project
+                .change_url(&PlatformId::PullRequest(2))
+                .unwrap()
+                .as_str(),
+            "https://github.corp.example/org/project/pull/2"
+        );
+    }
+
     #[test]
     fn gitlab_project_url_parsing() {
         // With or without the trailing slash
@@ -336,4 +921,232 @@ mod test {
         };
         assert_eq!(project.to_string(), "https://gitlab.host.com/group/project")
     }
+
+    #[test]
+    fn gitea_project_url_parsing() {
+        const URLS: &[&str] = &[
+            "https://codeberg.org/forgejo/forgejo",
+            "https://codeberg.org/forgejo/forgejo/",
+            "https://codeberg.org/forgejo/forgejo.git",
+            "ssh://git@codeberg.org/forgejo/forgejo.git",
+        ];
+        let expected = GiteaProject {
+            host: "codeberg.org".to_owned(),
+            owner: "forgejo".to_owned(),
+            project: "forgejo".to_owned(),
+        };
+        for url in URLS {
+            let actual = GiteaProject::from_str(url).unwrap();
+            assert_eq!(expected, actual);
+        }
+        assert!(GiteaProject::from_str("https://git.example.com/org/project").is_err());
+    }
+
+    #[test]
+    fn gitea_project_url_construction() {
+        let project = GiteaProject {
+            host: "git.example.com".to_owned(),
+            owner: "org".to_owned(),
+            project: "project".to_owned(),
+        };
+        assert_eq!(project.to_string(), "https://git.example.com/org/project");
+        assert_eq!(
+            project.change_url(&PlatformId::Issue(1)).unwrap().as_str(),
+            "https://git.example.com/org/project/issues/1"
+        );
+        assert_eq!(
+            project
+                .change_url(&PlatformId::PullRequest(2))
+                .unwrap()
+                .as_str(),
+            "https://git.example.com/org/project/pulls/2"
+        );
+        assert_eq!(
+            project.compare_url("v0.1.0", "v0.2.0").unwrap().as_str(),
+            "https://git.example.com/org/project/compare/v0.1.0...v0.2.0"
+        );
+    }
+
+    #[test]
+    fn bitbucket_project_url_parsing() {
+        const URLS: &[&str] = &[
+            "https://bitbucket.org/workspace/project",
+            "https://user@bitbucket.org/workspace/project.git",
+            "git@bitbucket.org:workspace/project.git",
+        ];
+        let expected = BitbucketProject {
+            host: "bitbucket.org".to_owned(),
+            workspace: "workspace".to_owned(),
+            project: "project".to_owned(),
+        };
+        for url in URLS {
+            let actual = BitbucketProject::try_from(&parse_url(url).unwrap()).unwrap();
+            assert_eq!(expected, actual);
+        }
+
+        const SERVER_URLS: &[&str] = &[
+            "https://bitbucket.example.com/scm/proj/repo.git",
+            "https://bitbucket.example.com/projects/PROJ/repos/repo/browse",
+        ];
+        for url in SERVER_URLS {
+            let res = BitbucketProject::try_from(&parse_url(url).unwrap());
+            assert!(matches!(
+                res,
+                Err(Error::UnsupportedBitbucketServerProject(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn bitbucket_project_url_construction() {
+        let project = BitbucketProject {
+            host: "bitbucket.org".to_owned(),
+            workspace: "workspace".to_owned(),
+            project: "project".to_owned(),
+        };
+        assert_eq!(
+            project.to_string(),
+            "https://bitbucket.org/workspace/project"
+        );
+        assert_eq!(
+            project.change_url(&PlatformId::Issue(1)).unwrap().as_str(),
+            "https://bitbucket.org/workspace/project/issues/1"
+        );
+        assert_eq!(
+            project
+                .change_url(&PlatformId::PullRequest(2))
+                .unwrap()
+                .as_str(),
+            "https://bitbucket.org/workspace/project/pull-requests/2"
+        );
+    }
+
+    #[test]
+    fn azure_devops_project_url_parsing() {
+        const URLS: &[&str] = &[
+            "https://dev.azure.com/org/project/_git/repo",
+            "https://org@dev.azure.com/org/project/_git/repo",
+            "git@ssh.dev.azure.com:v3/org/project/repo",
+        ];
+        let expected = AzureDevOpsProject {
+            organization_url: "https://dev.azure.com/org".to_owned(),
+            project: "project".to_owned(),
+            repository: "repo".to_owned(),
+        };
+        for url in URLS {
+            let actual = AzureDevOpsProject::try_from(&parse_url(url).unwrap()).unwrap();
+            assert_eq!(expected, actual);
+        }
+
+        let actual =
+            AzureDevOpsProject::from_str("https://org.visualstudio.com/project/_git/repo").unwrap();
+        assert_eq!(
+            actual.to_string(),
+            "https://org.visualstudio.com/project/_git/repo"
+        );
+        assert!(AzureDevOpsProject::from_str("https://dev.azure.com/org/project").is_err());
+    }
+
+    #[test]
+    fn azure_devops_project_url_construction() {
+        let project = AzureDevOpsProject {
+            organization_url: "https://dev.azure.com/org".to_owned(),
+            project: "project".to_owned(),
+            repository: "repo".to_owned(),
+        };
+        assert_eq!(
+            project.to_string(),
+            "https://dev.azure.com/org/project/_git/repo"
+        );
+        assert_eq!(
+            project.change_url(&PlatformId::Issue(1)).unwrap().as_str(),
+            "https://dev.azure.com/org/project/_workitems/edit/1"
+        );
+        assert_eq!(
+            project
+                .change_url(&PlatformId::PullRequest(2))
+                .unwrap()
+                .as_str(),
+            "https://dev.azure.com/org/project/_git/repo/pullrequest/2"
+        );
+    }
+
+    #[test]
+    fn remote_url_parsing() {
+        let cases = [
+            (
+                "git@github.com:org/project.git",
+                "ssh://git@github.com/org/project.git",
+            ),
+            (
+                "ssh://git@bitbucket.example.com:7999/proj/repo.git",
+                "ssh://git@bitbucket.example.com:7999/proj/repo.git",
+            ),
+            (
+                "https://github.com/org/project",
+                "https://github.com/org/project",
+            ),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(parse_url(url).unwrap().as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn platform_selection() {
+        let url = Url::parse("https://git.example.com/org/project").unwrap();
+        assert!(try_from(&url).is_err());
+        assert!(matches!(
+            try_from_platform(&url, Some(Platform::Gitea)).unwrap(),
+            Project::GiteaProject(_)
+        ));
+        assert!(matches!(
+            try_from_platform(&url, Some(Platform::GitLab)).unwrap(),
+            Project::GitLabProject(_)
+        ));
+        assert_eq!(Platform::Gitea, Platform::from_str("forgejo").unwrap());
+        assert_eq!(
+            Platform::AzureDevOps,
+            Platform::from_str("azure-devops").unwrap()
+        );
+    }
+
+    #[test]
+    fn platform_deserialization() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            platform: Platform,
+        }
+        let cases = [
+            ("github", Platform::GitHub),
+            ("gitlab", Platform::GitLab),
+            ("gitea", Platform::Gitea),
+            ("forgejo", Platform::Gitea),
+            ("bitbucket", Platform::Bitbucket),
+            ("azure-devops", Platform::AzureDevOps),
+            ("azuredevops", Platform::AzureDevOps),
+        ];
+        for (s, expected) in cases {
+            let wrapper: Wrapper = toml::from_str(&format!("platform = \"{s}\"")).unwrap();
+            assert_eq!(expected, wrapper.platform);
+            assert_eq!(expected, Platform::from_str(s).unwrap());
+        }
+    }
+
+    #[test]
+    fn gitlab_project_compare_url_construction() {
+        let project = GitLabProject {
+            root_url: "group".to_owned(),
+            host: "gitlab.host.com".to_owned(),
+            project: "project".to_owned(),
+        };
+        assert_eq!(
+            project.compare_url("v0.1.0", "v0.2.0").unwrap().as_str(),
+            "https://gitlab.host.com/group/project/-/compare/v0.1.0...v0.2.0"
+        );
+        assert_eq!(
+            project.tag_url("v0.1.0").unwrap().as_str(),
+            "https://gitlab.host.com/group/project/-/tags/v0.1.0"
+        )
+    }
 }
@@ -2,7 +2,9 @@
 
 use lazy_static::lazy_static;
 use std::{path::Path, sync::Mutex};
-use unclog::{ChangeSetComponentPath, Changelog, Config, EntryReleasePath, PlatformId};
+use unclog::{
+    ChangeSetComponentPath, Changelog, Config, EntryReleasePath, Error, PlatformId, TemplatesConfig,
+};
 
 lazy_static! {
     static ref LOGGING_INITIALIZED: Mutex<u8> = Mutex::new(0);
@@ -31,7 +33,36 @@ component2 = { name = "Component 2", path = "2nd-component" }
     let config = toml::from_str(CONFIG_FILE).unwrap();
     let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
     let expected = std::fs::read_to_string("./tests/full/expected.md").unwrap();
-    assert_eq!(expected, changelog.render_all(&config));
+    assert_eq!(expected, changelog.render_all(&config).unwrap());
+}
+
+#[test]
+fn check_changelog_file() {
+    const CONFIG_FILE: &str = r#"
+[components.all]
+component1 = { name = "component1" }
+component2 = { name = "Component 2", path = "2nd-component" }
+"#;
+
+    init_logger();
+    let config = toml::from_str(CONFIG_FILE).unwrap();
+    let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
+    let rendered = changelog.render_all(&config).unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let target = tmpdir.path().join("CHANGELOG.md");
+
+    // Differences in the number of trailing newlines are ignored.
+    std::fs::write(&target, format!("{rendered}\n\n")).unwrap();
+    unclog::check_changelog_file(&target, &rendered).unwrap();
+
+    std::fs::write(&target, rendered.replace("## v0.2.0", "## v0.2.0-old")).unwrap();
+    match unclog::check_changelog_file(&target, &rendered) {
+        Err(unclog::Error::ChangelogOutOfDate(path, diff)) => {
+            assert_eq!(path, target.display().to_string());
+            assert!(diff.contains("\n-## v0.2.0-old\n+## v0.2.0\n"), "{diff}");
+        }
+        other => panic!("expected the changelog to be out of date, got {other:?}"),
+    }
 }
 
 #[test]
@@ -46,7 +77,7 @@ component2 = { name = "Component 2", path = "2nd-component" }
     let config = toml::from_str(CONFIG_FILE).unwrap();
     let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
     let expected = std::fs::read_to_string("./tests/full/expected-released-only.md").unwrap();
-    assert_eq!(expected, changelog.render_released(&config));
+    assert_eq!(expected, changelog.render_released(&config).unwrap());
 }
 
 #[test]
@@ -65,7 +96,7 @@ component2 = { name = "Component 2", path = "2nd-component" }
     let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
     let expected =
         std::fs::read_to_string("./tests/full/expected-sorted-by-entry-text.md").unwrap();
-    assert_eq!(expected, changelog.render_all(&config));
+    assert_eq!(expected, changelog.render_all(&config).unwrap());
 }
 
 #[test]
@@ -86,7 +117,427 @@ component2 = { name = "Component 2", path = "2nd-component" }
     let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
     let expected =
         std::fs::read_to_string("./tests/full/expected-sorted-by-release-date.md").unwrap();
-    assert_eq!(expected, changelog.render_all(&config));
+    assert_eq!(expected, changelog.render_all(&config).unwrap());
+}
+
+#[test]
+fn release_subsets() {
+    const CONFIG_FILE: &str = r#"
+[components.all]
+component1 = { name = "component1" }
+component2 = { name = "Component 2", path = "2nd-component" }
+"#;
+
+    init_logger();
+    let config = toml::from_str(CONFIG_FILE).unwrap();
+    let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
+
+    let rendered = changelog.render_release(&config, "v0.2.0").unwrap();
+    assert!(rendered.starts_with("## v0.2.0\n\n*27 Feb 2021*"));
+    assert!(!rendered.contains("## v0.2.0-beta"));
+    assert!(changelog.render_release(&config, "v0.3.0").is_err());
+
+    let ids = |releases: Vec<&unclog::Release>| {
+        releases
+            .into_iter()
+            .map(|r| r.id.clone())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(
+        ids(changelog
+            .releases_in_range(Some("v0.2.0-alpha"), Some("v0.2.0"))
+            .unwrap()),
+        vec!["v0.2.0", "v0.2.0-beta", "v0.2.0-alpha"]
+    );
+    assert_eq!(
+        ids(changelog
+            .releases_in_range(Some("0.2.0"), None::<&str>)
+            .unwrap()),
+        vec!["v0.2.1", "v0.2.0"]
+    );
+    assert_eq!(
+        ids(changelog
+            .releases_in_range(None::<&str>, Some("v0.1.1"))
+            .unwrap()),
+        vec!["v0.1.1", "v0.1.0"]
+    );
+    assert!(changelog
+        .render_release_range(&config, Some("v1.0.0"), None::<&str>)
+        .is_err());
+}
+
+#[test]
+fn configured_sections() {
+    const CONFIG_FILE: &str = r#"
+[change_set_sections.all]
+features = { title = "Features ✨", order = 2, description = "New functionality." }
+breaking-changes = { title = "Breaking Changes ⚠", order = 1 }
+improvements = {}
+
+[components.all]
+component1 = { name = "component1" }
+component2 = { name = "Component 2", path = "2nd-component" }
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
+    let expected = r#"## v0.2.0
+
+*27 Feb 2021*
+
+It's finally out, yay!
+
+### Breaking Changes ⚠
+
+- Let the tune meet the unlawful disaster
+- Educate the specialist vigorously
+
+### Features ✨
+
+New functionality.
+
+- Stir the engineer with the foolish sound
+- Attend the entry with an ambitious blank"#;
+    assert_eq!(
+        expected,
+        changelog.render_release(&config, "v0.2.0").unwrap()
+    );
+
+    let titles = changelog
+        .find_release(unclog::Version::parse("0.2.0-beta").unwrap())
+        .unwrap()
+        .changes
+        .sections
+        .iter()
+        .map(|s| s.title.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(titles, vec!["Features ✨", "IMPROVEMENTS"]);
+
+    let result = Changelog::add_unreleased_entry(
+        &config,
+        "./tests/nonexistent",
+        "featurs",
+        None::<&str>,
+        "1-typo",
+        "- Typo",
+    );
+    assert!(matches!(result, Err(unclog::Error::SectionNotDefined(_))));
+    assert!(!Path::new("./tests/nonexistent").exists());
+}
+
+#[test]
+fn full_with_templates() {
+    const CONFIG_FILE: &str = r#"
+release_date_formats = ["*%d %b %Y*"]
+
+[templates]
+release = """
+## [{{ id }}]{{#if date}} - {{ date }}{{/if}}
+
+{{{ content }}}"""
+change_set_section = """
+#### {{ title }}
+
+{{{ content }}}"""
+component_section = "{{ bullet }} **{{ name }}**\n{{{ content }}}"
+entry = "{{{ details }}}{{> marker }}"
+
+[templates.partials]
+marker = " ✓"
+
+[components.all]
+component1 = { name = "component1" }
+component2 = { name = "Component 2", path = "2nd-component" }
+"#;
+
+    init_logger();
+    let config = toml::from_str(CONFIG_FILE).unwrap();
+    let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
+    let expected = std::fs::read_to_string("./tests/full/expected-with-templates.md").unwrap();
+    assert_eq!(expected, changelog.render_released(&config).unwrap());
+}
+
+#[test]
+fn template_render_errors() {
+    const CONFIG_FILE: &str = r#"
+[templates]
+entry = "{{{ details }}}{{> missing }}"
+
+[components.all]
+component1 = { name = "component1" }
+component2 = { name = "Component 2", path = "2nd-component" }
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
+    // Rendering must fail rather than silently falling back to the default
+    // layout.
+    let err = changelog.render_released(&config).unwrap_err();
+    assert!(
+        matches!(err, unclog::Error::HandlebarsTemplateRender(_)),
+        "{err}"
+    );
+    assert!(changelog.render_keep_a_changelog_released(&config).is_err());
+}
+
+#[test]
+fn template_changes_after_rendering() {
+    const CONFIG_FILE: &str = r#"
+[components.all]
+component1 = { name = "component1" }
+component2 = { name = "Component 2", path = "2nd-component" }
+"#;
+
+    init_logger();
+    let mut config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
+    let default = changelog.render_all(&config).unwrap();
+    assert!(!default.contains("TEMPLATED"));
+
+    // Templates set after rendering must be picked up by the next render.
+    config
+        .templates
+        .set_template(
+            TemplatesConfig::ENTRY,
+            Some("TEMPLATED {{> suffix }}".to_owned()),
+        )
+        .unwrap();
+    config
+        .templates
+        .set_partial("suffix", Some("v1".to_owned()));
+    assert!(changelog
+        .render_all(&config)
+        .unwrap()
+        .contains("TEMPLATED v1"));
+    config
+        .templates
+        .set_partial("suffix", Some("v2".to_owned()));
+    assert!(changelog
+        .render_all(&config)
+        .unwrap()
+        .contains("TEMPLATED v2"));
+
+    config
+        .templates
+        .set_template(TemplatesConfig::ENTRY, None)
+        .unwrap();
+    assert_eq!(default, changelog.render_all(&config).unwrap());
+    assert!(config.templates.set_template("unknown", None).is_err());
+}
+
+#[test]
+fn release_headings() {
+    const CONFIG_FILE: &str = r###"
+project_url = "https://github.com/org/project"
+release_date_formats = ["*%d %b %Y*"]
+
+[templates]
+release_heading = "## [{{ id }}]{{#if compare_url}}({{ compare_url }}){{/if}}{{#if date}} - {{ date }}{{/if}}"
+
+[components.all]
+component1 = { name = "component1" }
+component2 = { name = "Component 2", path = "2nd-component" }
+"###;
+
+    init_logger();
+    let config = toml::from_str(CONFIG_FILE).unwrap();
+    let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
+    let rendered = changelog
+        .render_release_range(&config, Some("v0.1.0"), Some("v0.2.0-alpha"))
+        .unwrap();
+    let headings = rendered
+        .lines()
+        .filter(|line| line.starts_with("## "))
+        .collect::<Vec<&str>>();
+    assert_eq!(
+        headings,
+        vec![
+            "## [v0.2.0-alpha](https://github.com/org/project/compare/v0.1.1...v0.2.0-alpha) - 2021-02-03",
+            "## [v0.1.1](https://github.com/org/project/compare/v0.1.0...v0.1.1) - 2021-03-31",
+            "## [v0.1.0] - 2021-01-08",
+        ]
+    );
+}
+
+#[test]
+fn full_keep_a_changelog() {
+    const CONFIG_FILE: &str = r#"
+project_url = "https://github.com/org/project"
+release_date_formats = ["*%d %b %Y*"]
+
+[components.all]
+component1 = { name = "component1" }
+component2 = { name = "Component 2", path = "2nd-component" }
+"#;
+
+    init_logger();
+    let config = toml::from_str(CONFIG_FILE).unwrap();
+    let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
+    let expected = std::fs::read_to_string("./tests/full/expected-keep-a-changelog.md").unwrap();
+    assert_eq!(
+        expected,
+        changelog.render_keep_a_changelog_all(&config).unwrap()
+    );
+}
+
+#[test]
+fn import_markdown() {
+    const CONFIG_FILE: &str = r#"
+[components.all]
+component1 = { name = "component1" }
+component2 = { name = "Component 2", path = "2nd-component" }
+"#;
+
+    init_logger();
+    let config = toml::from_str(CONFIG_FILE).unwrap();
+    let expected = std::fs::read_to_string("./tests/full/expected.md").unwrap();
+    let parsed = Changelog::parse_markdown(&config, &expected).unwrap();
+    assert_eq!(expected, parsed.render_all(&config).unwrap());
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let changelog_path = tmpdir.path().join("CHANGELOG.md");
+    std::fs::write(
+        &changelog_path,
+        r#"# Changelog
+
+## [Unreleased]
+
+### Added
+
+- Something new ([\#12](https://github.com/org/project/pull/12))
+
+## [1.1.0] - 2024-01-02
+
+### Fixed
+
+- Fix a bug
+  that spans multiple lines ([#7](https://github.com/org/project/issues/7))
+- Fix another bug
+- [Component 2](2nd-component)
+  - Fix a component bug ([#8](https://github.com/org/project/issues/8))
+
+## Older releases
+
+Some historical content.
+"#,
+    )
+    .unwrap();
+    let path = tmpdir.path().join(".changelog");
+    Changelog::import(&config, &path, &changelog_path).unwrap();
+
+    let entry = |p: &str| std::fs::read_to_string(path.join(p)).unwrap();
+    assert_eq!(
+        entry("unreleased/added/12-something-new.md"),
+        "- Something new ([\\#12](https://github.com/org/project/pull/12))\n"
+    );
+    assert_eq!(entry("1.1.0/summary.md"), "2024-01-02\n");
+    assert_eq!(
+        entry("1.1.0/fixed/7-fix-a-bug.md"),
+        "- Fix a bug\n  that spans multiple lines ([#7](https://github.com/org/project/issues/7))\n"
+    );
+    assert_eq!(
+        entry("1.1.0/fixed/0-fix-another-bug.md"),
+        "- Fix another bug\n"
+    );
+    assert_eq!(
+        entry("1.1.0/fixed/component2/8-fix-a-component-bug.md"),
+        "- Fix a component bug ([#8](https://github.com/org/project/issues/8))\n"
+    );
+    assert_eq!(
+        entry("epilogue.md"),
+        "## Older releases\n\nSome historical content.\n"
+    );
+
+    let imported = Changelog::read_from_dir(&config, &path).unwrap();
+    assert_eq!(imported.releases.len(), 1);
+    assert_eq!(
+        imported.releases[0].maybe_date,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+    );
+}
+
+#[test]
+fn import_linked_release_headings() {
+    init_logger();
+    let config = Config::default();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path().join(".changelog");
+    Changelog::import(&config, &path, "./tests/import/linked-headings.md").unwrap();
+
+    let imported = Changelog::read_from_dir(&config, &path).unwrap();
+    assert!(imported.epilogue.is_none());
+    let releases = imported
+        .releases
+        .iter()
+        .map(|r| (r.id.as_str(), r.maybe_date))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        releases,
+        vec![
+            ("1.1.0", chrono::NaiveDate::from_ymd_opt(2024, 1, 2)),
+            ("1.0.0", chrono::NaiveDate::from_ymd_opt(2023, 12, 1)),
+        ]
+    );
+    assert!(path.join("unreleased/added/12-something-new.md").is_file());
+    assert!(path.join("1.0.0/added/1-initial-release.md").is_file());
+}
+
+#[test]
+fn import_validates_against_config() {
+    const CHANGELOG: &str = r#"## v1.0.0
+
+### Features
+
+- Add a feature ([#1](https://github.com/org/project/issues/1))
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(
+        r#"
+[change_set_sections.all]
+breaking-changes = {}
+"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        Changelog::parse_markdown(&config, CHANGELOG),
+        Err(Error::SectionNotDefined(section)) if section == "features"
+    ));
+
+    let config: Config = toml::from_str(
+        r#"
+[entry_ids]
+pattern = "[A-Z]+-[0-9]+"
+"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        Changelog::parse_markdown(&config, CHANGELOG),
+        Err(Error::EntryIdPatternMismatch(filename, _)) if filename == "1-add-a-feature.md"
+    ));
+}
+
+#[test]
+fn json_round_trip() {
+    const CONFIG_FILE: &str = r#"
+[components.all]
+component1 = { name = "component1" }
+component2 = { name = "Component 2", path = "2nd-component" }
+"#;
+
+    init_logger();
+    let config = toml::from_str(CONFIG_FILE).unwrap();
+    let changelog = Changelog::read_from_dir(&config, "./tests/full").unwrap();
+    let json = serde_json::to_string(&changelog).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["releases"][0]["id"], "v0.2.1");
+    assert_eq!(value["releases"][0]["version"], "0.2.1");
+    assert_eq!(value["releases"][0]["date"], serde_json::Value::Null);
+    assert_eq!(value["unreleased"]["sections"][0]["entries"][0]["id"], 45);
+    let deserialized: Changelog = serde_json::from_str(&json).unwrap();
+    assert_eq!(changelog, deserialized);
 }
 
 #[test]
@@ -94,17 +545,19 @@ fn change_template_rendering() {
     init_logger();
     let config = Config::read_from_file("./tests/full/config.toml").unwrap();
     let cases = vec![
-        (PlatformId::Issue(123), "- This introduces a new *breaking* change\n  ([\\#123](https://github.com/org/project/issues/123))"),
-        (PlatformId::PullRequest(23), "- This introduces a new *breaking* change\n  ([\\#23](https://github.com/org/project/pull/23))"),
+        (vec![PlatformId::Issue(123)], "- This introduces a new *breaking* change\n  ([\\#123](https://github.com/org/project/issues/123))"),
+        (vec![PlatformId::PullRequest(23)], "- This introduces a new *breaking* change\n  ([\\#23](https://github.com/org/project/pull/23))"),
+        (vec![PlatformId::Commit("1a2b3c4".to_owned())], "- This introduces a new *breaking* change\n  ([1a2b3c4](https://github.com/org/project/commit/1a2b3c4))"),
+        (vec![PlatformId::Issue(123), PlatformId::Issue(124), PlatformId::PullRequest(23)], "- This introduces a new *breaking* change\n  ([\\#123](https://github.com/org/project/issues/123),\n  [\\#124](https://github.com/org/project/issues/124),\n  [\\#23](https://github.com/org/project/pull/23))"),
     ];
-    for (platform_id, expected) in cases {
+    for (platform_ids, expected) in cases {
         let actual = Changelog::render_unreleased_entry_from_template(
             &config,
             Path::new("./tests/full"),
             "breaking-changes",
             None,
             "some-new-breaking-change",
-            platform_id,
+            &platform_ids,
             "This introduces a new *breaking* change",
         )
         .unwrap();
@@ -112,6 +565,109 @@ fn change_template_rendering() {
     }
 }
 
+#[test]
+fn change_url_templates() {
+    const CONFIG_FILE: &str = r#"
+project_url = "https://review.example.com/project"
+
+[change_urls]
+issue = "https://bugzilla.example.com/show_bug.cgi?id={id}"
+pull_request = "https://review.example.com/c/project/+/{id}"
+commit = "{project_url}/+/{id}"
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let rendered = Changelog::render_unreleased_entry_from_template(
+        &config,
+        Path::new("./tests/full"),
+        "bug-fixes",
+        None,
+        "fix-timeout",
+        &[
+            PlatformId::Issue(12),
+            PlatformId::PullRequest(34),
+            PlatformId::Commit("abc1234".to_owned()),
+        ],
+        "Fix the timeout",
+    )
+    .unwrap();
+    assert_eq!(
+        "- Fix the timeout ([\\#12](https://bugzilla.example.com/show_bug.cgi?id=12),\n  [\\#34](https://review.example.com/c/project/+/34),\n  [abc1234](https://review.example.com/project/+/abc1234))",
+        rendered
+    );
+
+    // Without a template for tickets, and with an unrecognized project URL,
+    // there is no way to link to a ticket.
+    assert!(Changelog::render_unreleased_entry_from_template(
+        &config,
+        Path::new("./tests/full"),
+        "bug-fixes",
+        None,
+        "fix-timeout",
+        &[PlatformId::Ticket("PROJ-1".to_owned())],
+        "Fix the timeout",
+    )
+    .is_err());
+}
+
+#[test]
+fn ticket_entry_ids() {
+    const CONFIG_FILE: &str = r#"
+project_url = "https://github.com/org/project"
+
+[entry_ids]
+pattern = "[A-Z]+-(?P<number>\\d+)"
+sort_key = "number"
+
+[change_urls]
+ticket = "https://example.atlassian.net/browse/{id}"
+"#;
+    const EXPECTED: &str = "## Unreleased
+
+### BUG FIXES
+
+- Fix the crash ([PROJ-99](https://example.atlassian.net/browse/PROJ-99))
+- Fix the leak ([PROJ-100](https://example.atlassian.net/browse/PROJ-100))
+- Fix the timeout ([PROJ-1234](https://example.atlassian.net/browse/PROJ-1234))";
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let changelog = Changelog::read_from_dir(&config, "./tests/ticket-ids").unwrap();
+    assert_eq!(EXPECTED, changelog.render_unreleased(&config).unwrap());
+    let entry = changelog
+        .entries()
+        .next()
+        .map(|entry_path| entry_path.entry().clone())
+        .unwrap();
+    assert_eq!(99, entry.id);
+    assert_eq!(
+        vec![PlatformId::Ticket("PROJ-99".to_owned())],
+        entry.platform_ids
+    );
+
+    let rendered = Changelog::render_unreleased_entry_from_template(
+        &config,
+        Path::new("./tests/ticket-ids"),
+        "bug-fixes",
+        None,
+        "fix-timeout",
+        &[
+            PlatformId::Ticket("PROJ-1234".to_owned()),
+            PlatformId::PullRequest(23),
+        ],
+        "Fix the timeout",
+    )
+    .unwrap();
+    assert_eq!(
+        "- Fix the timeout ([PROJ-1234](https://example.atlassian.net/browse/PROJ-1234),\n  [\\#23](https://github.com/org/project/pull/23))",
+        rendered
+    );
+
+    // Entries that don't match the configured pattern fail to load.
+    assert!(Changelog::read_from_dir(&Config::default(), "./tests/ticket-ids").is_err());
+}
+
 #[test]
 fn entry_iteration() {
     const CONFIG_FILE: &str = r#"
@@ -194,3 +750,641 @@ component2 = { name = "Component 2", path = "2nd-component" }
         }
     }
 }
+
+#[test]
+fn lint_entries() {
+    const CONFIG_FILE: &str = r#"
+wrap = 40
+
+[lint.rules]
+trailing-whitespace = "off"
+wrong-extension = "warning"
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path();
+    let section = path.join("unreleased").join("bug-fixes");
+    std::fs::create_dir_all(&section).unwrap();
+    let write = |name: &str, content: &str| std::fs::write(section.join(name), content).unwrap();
+    write("1-good.md", "- A perfectly fine entry\n");
+    write(
+        "2-bad.md",
+        "+++\nbreaking = true\n+++\n* Wrong bullet  \n  and a line that is much too long for the wrap width\n",
+    );
+    write(
+        "3-placeholder.md",
+        "<!--\n    Add your entry's details here -->\n",
+    );
+    write("4-empty.md", "\n\n");
+    write("5-wrong-ext.txt", "- Ignored\n");
+    write(
+        "6-bad-front-matter.md",
+        "+++\nbreaking = true\nissues = \"7\"\n+++\n- Some entry\n",
+    );
+    write("no-number.md", "- Some entry\n");
+    write(".gitkeep", "");
+
+    let issues = Changelog::lint(&config, path)
+        .unwrap()
+        .into_iter()
+        .map(|issue| {
+            let file = issue.path.strip_prefix(&section).unwrap().display();
+            let line = issue.maybe_line.map(|l| l.to_string()).unwrap_or_default();
+            format!("{file}:{line}:{}:{}", issue.severity, issue.rule)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        issues,
+        vec![
+            "2-bad.md:4:error:bullet-style",
+            "2-bad.md:5:warning:line-length",
+            "3-placeholder.md:1:error:bullet-style",
+            "3-placeholder.md:1:error:html-comment",
+            "4-empty.md::error:empty-entry",
+            "6-bad-front-matter.md:3:error:invalid-entry",
+            "no-number.md::error:invalid-entry",
+            "5-wrong-ext.txt::warning:wrong-extension",
+        ]
+    );
+}
+
+#[test]
+fn added_entries_read_back() {
+    const CONFIG_FILE: &str = r#"
+project_url = "https://github.com/org/project"
+
+[change_urls]
+ticket = "https://example.atlassian.net/browse/{id}"
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path();
+    let add = |id: &str, platform_ids: &[PlatformId]| {
+        Changelog::add_unreleased_entry_from_template(
+            &config,
+            path,
+            "features",
+            None,
+            id,
+            platform_ids,
+            "Some change",
+        )
+        .unwrap()
+    };
+    add("issue", &[PlatformId::Issue(1)]);
+    add("pull-request", &[PlatformId::PullRequest(2)]);
+    add("both", &[PlatformId::Issue(3), PlatformId::PullRequest(4)]);
+    add(
+        "with-ticket",
+        &[
+            PlatformId::Issue(5),
+            PlatformId::Ticket("PROJ-1".to_owned()),
+        ],
+    );
+    // Commit hashes starting with digits must not be mistaken for issues.
+    add(
+        "commit",
+        &[
+            PlatformId::Issue(6),
+            PlatformId::Commit("1234abc".to_owned()),
+        ],
+    );
+    // Neither tickets nor commits can be loaded from filenames without an
+    // entry ID pattern.
+    for platform_id in [
+        PlatformId::Ticket("PROJ-2".to_owned()),
+        PlatformId::Commit("abc1234".to_owned()),
+    ] {
+        let res = Changelog::add_unreleased_entry_from_template(
+            &config,
+            path,
+            "features",
+            None,
+            "other",
+            &[platform_id],
+            "Some change",
+        );
+        assert!(matches!(res, Err(Error::EntryIdWithoutNumber(_))));
+    }
+
+    let changelog = Changelog::read_from_dir(&config, path).unwrap();
+    let unreleased = changelog.maybe_unreleased.unwrap();
+    let entries = unreleased.sections[0]
+        .entries
+        .iter()
+        .map(|e| (e.filename.as_str(), e.platform_ids.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        vec![
+            ("1-issue.md", vec![PlatformId::Issue(1)]),
+            ("2-pull-request.md", vec![PlatformId::PullRequest(2)]),
+            (
+                "3-4-both.md",
+                vec![PlatformId::Issue(3), PlatformId::PullRequest(4)]
+            ),
+            (
+                "5-with-ticket.md",
+                vec![
+                    PlatformId::Issue(5),
+                    PlatformId::Ticket("PROJ-1".to_owned())
+                ]
+            ),
+            (
+                "6-commit.md",
+                vec![
+                    PlatformId::Issue(6),
+                    PlatformId::Commit("1234abc".to_owned())
+                ]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn added_ticket_entries_read_back() {
+    const CONFIG_FILE: &str = r#"
+project_url = "https://github.com/org/project"
+
+[entry_ids]
+pattern = "[A-Z]+-(?P<number>\\d+)"
+sort_key = "number"
+
+[change_urls]
+ticket = "https://example.atlassian.net/browse/{id}"
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path();
+    let add = |id: &str, platform_ids: &[PlatformId]| {
+        Changelog::add_unreleased_entry_from_template(
+            &config,
+            path,
+            "features",
+            None,
+            id,
+            platform_ids,
+            "Some change",
+        )
+    };
+    add(
+        "ticket",
+        &[
+            PlatformId::Ticket("PROJ-1".to_owned()),
+            PlatformId::Ticket("PROJ-2".to_owned()),
+            PlatformId::Issue(3),
+        ],
+    )
+    .unwrap();
+    // Issue numbers can't be loaded from filenames with an entry ID pattern.
+    let res = add("issue", &[PlatformId::Issue(4)]);
+    assert!(matches!(res, Err(Error::EntryIdPatternMismatch(_, _))));
+
+    let changelog = Changelog::read_from_dir(&config, path).unwrap();
+    let unreleased = changelog.maybe_unreleased.unwrap();
+    let entries = unreleased.sections[0]
+        .entries
+        .iter()
+        .map(|e| (e.filename.as_str(), e.platform_ids.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        vec![(
+            "PROJ-1-ticket.md",
+            vec![
+                PlatformId::Ticket("PROJ-1".to_owned()),
+                PlatformId::Issue(3),
+                PlatformId::Ticket("PROJ-2".to_owned()),
+            ]
+        )]
+    );
+}
+
+#[test]
+fn scan_pull_requests() {
+    const CONFIG_FILE: &str = r#"
+project_url = "https://github.com/org/project"
+
+[scan]
+default_section = "unsorted"
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init(tmpdir.path()).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let commit = |message: &str| {
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => vec![],
+        };
+        let parents = parents.iter().collect::<Vec<_>>();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    };
+    commit("Old change (#1)");
+    let release = commit("Release v0.1.0");
+    repo.tag_lightweight("v0.1.0", &repo.find_object(release, None).unwrap(), false)
+        .unwrap();
+    commit("Add a feature (#5)");
+    commit("Merge pull request #6 from org/branch\n\nFix a bug");
+    commit("Already logged (#7)");
+    commit("Plan the roadmap (#2021)");
+
+    let path = tmpdir.path().join(".changelog");
+    let section = path.join("unreleased").join("features");
+    std::fs::create_dir_all(&section).unwrap();
+    std::fs::write(section.join("7-already-logged.md"), "- Already logged").unwrap();
+    // Only the first number in a filename counts as a reference.
+    std::fs::write(section.join("100-2021-roadmap.md"), "- Roadmap").unwrap();
+    let changelog = Changelog::read_from_dir(&config, &path).unwrap();
+    let found = |since| {
+        changelog
+            .find_pull_requests_without_entries(&path, since)
+            .unwrap()
+            .into_iter()
+            .map(|pr| (pr.number, pr.title))
+            .collect::<Vec<_>>()
+    };
+    let expected = vec![
+        (2021, "Plan the roadmap".to_owned()),
+        (6, "Fix a bug".to_owned()),
+        (5, "Add a feature".to_owned()),
+    ];
+    assert_eq!(found(Some("v0.1.0")), expected);
+    assert_eq!(found(None).len(), 4);
+
+    let pull_requests = changelog
+        .find_pull_requests_without_entries(&path, Some("v0.1.0"))
+        .unwrap();
+    Changelog::add_pull_request_entries(
+        &config,
+        &path,
+        &config.scan.default_section,
+        &pull_requests,
+    )
+    .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(path.join("unreleased/unsorted/6-fix-a-bug.md")).unwrap(),
+        "+++\npull_requests = [6]\n+++\n- Fix a bug ([\\#6](https://github.com/org/project/pull/6))"
+    );
+    assert!(path.join("unreleased/unsorted/5-add-a-feature.md").exists());
+
+    // Nothing is added if the entries can't be created.
+    let defined_sections: Config = toml::from_str(&format!(
+        "{CONFIG_FILE}\n[change_set_sections.all]\nfeatures = {{}}\n"
+    ))
+    .unwrap();
+    let err = Changelog::add_pull_request_entries(
+        &defined_sections,
+        &path,
+        &defined_sections.scan.default_section,
+        &pull_requests,
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::SectionNotDefined(_)), "{err}");
+    let ticket_ids: Config = toml::from_str(&format!(
+        "{CONFIG_FILE}\n[entry_ids]\npattern = \"[A-Z]+-[0-9]+\"\n"
+    ))
+    .unwrap();
+    let err = Changelog::add_pull_request_entries(&ticket_ids, &path, "features", &pull_requests)
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::PullRequestEntriesWithEntryIdPattern),
+        "{err}"
+    );
+    assert!(!path.join("unreleased/features/5-add-a-feature.md").exists());
+}
+
+#[test]
+fn entry_id_from_branch() {
+    init_logger();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init(tmpdir.path()).unwrap();
+    let path = tmpdir.path().join(".changelog");
+    std::fs::create_dir(&path).unwrap();
+
+    let config = Config::default();
+    repo.set_head("refs/heads/1234-fix-timeouts").unwrap();
+    assert_eq!(
+        Changelog::entry_id_from_branch(&config, &path).unwrap(),
+        Some((
+            "1234-fix-timeouts".to_owned(),
+            Some(PlatformId::Issue(1234))
+        ))
+    );
+    repo.set_head("refs/heads/main").unwrap();
+    assert_eq!(
+        Changelog::entry_id_from_branch(&config, &path).unwrap(),
+        None
+    );
+
+    let config: Config = toml::from_str(
+        r#"
+[branches]
+pattern = "(?P<pull_request>[0-9]+)-.+"
+"#,
+    )
+    .unwrap();
+    repo.set_head("refs/heads/42-some-change").unwrap();
+    assert_eq!(
+        Changelog::entry_id_from_branch(&config, &path).unwrap(),
+        Some((
+            "42-some-change".to_owned(),
+            Some(PlatformId::PullRequest(42))
+        ))
+    );
+}
+
+#[test]
+fn release_commit_and_tag() {
+    const CONFIG_FILE: &str = r#"
+[release]
+commit_message = "chore: release {version}"
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init(tmpdir.path()).unwrap();
+    let mut repo_config = repo.config().unwrap();
+    repo_config.set_str("user.name", "Test").unwrap();
+    repo_config
+        .set_str("user.email", "test@example.com")
+        .unwrap();
+
+    let path = tmpdir.path().join(".changelog");
+    let section = path.join("unreleased").join("features");
+    std::fs::create_dir_all(&section).unwrap();
+    std::fs::write(section.join("1-something.md"), "- Something").unwrap();
+    std::fs::write(path.join("unreleased").join("summary.md"), "A summary").unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["."], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = repo.signature().unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+        .unwrap();
+
+    Changelog::prepare_release_dir(&config, &path, "v0.1.0").unwrap();
+    let changelog_file = tmpdir.path().join("CHANGELOG.md");
+    std::fs::write(&changelog_file, "# CHANGELOG\n").unwrap();
+    // Changes that are already staged must not be swept into the release
+    // commit.
+    std::fs::write(tmpdir.path().join("unrelated.txt"), "Unrelated").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("unrelated.txt")).unwrap();
+    index.write().unwrap();
+    let res = Changelog::commit_release(
+        &config,
+        &path,
+        "v0.1.0",
+        std::slice::from_ref(&changelog_file),
+    );
+    assert!(matches!(res, Err(Error::UnrelatedStagedChanges(_))));
+    index.remove_path(Path::new("unrelated.txt")).unwrap();
+    index.write().unwrap();
+    std::fs::remove_file(tmpdir.path().join("unrelated.txt")).unwrap();
+
+    Changelog::commit_release(&config, &path, "v0.1.0", &[changelog_file]).unwrap();
+    let changelog = Changelog::read_from_dir(&config, &path).unwrap();
+    changelog.tag_release(&config, &path, "v0.1.0").unwrap();
+
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.message(), Some("chore: release v0.1.0"));
+    let tree = head.tree().unwrap();
+    for committed in [
+        ".changelog/v0.1.0/features/1-something.md",
+        ".changelog/unreleased/.gitkeep",
+        "CHANGELOG.md",
+    ] {
+        assert!(tree.get_path(Path::new(committed)).is_ok(), "{committed}");
+    }
+    assert!(tree
+        .get_path(Path::new(".changelog/unreleased/features/1-something.md"))
+        .is_err());
+    assert!(repo.statuses(None).unwrap().is_empty());
+
+    let tag = repo
+        .revparse_single("v0.1.0")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    assert_eq!(tag.target_id(), head.id());
+    assert_eq!(
+        tag.message(),
+        Some("## v0.1.0\n\nA summary\n\n### FEATURES\n\n- Something")
+    );
+}
+
+#[test]
+fn release_dates_from_git_tags() {
+    const CONFIG_FILE: &str = r#"
+sort_releases_by = ["date"]
+release_date_source = "git-tag"
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init(tmpdir.path()).unwrap();
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    // 2024-01-02 and 2024-03-04, both at 23:30 UTC, but in a time zone in
+    // which the latter is already the next day.
+    let old =
+        git2::Signature::new("Test", "test@example.com", &git2::Time::new(1704238200, 0)).unwrap();
+    let new =
+        git2::Signature::new("Test", "test@example.com", &git2::Time::new(1709595000, 60)).unwrap();
+    let commit = repo
+        .commit(Some("HEAD"), &old, &old, "Initial", &tree, &[])
+        .unwrap();
+    let commit = repo.find_object(commit, None).unwrap();
+    // Lightweight tags take their date from the commit, while annotated tags
+    // take their date from the tagger.
+    repo.tag_lightweight("v1.0.0", &commit, false).unwrap();
+    repo.tag("v0.9.0", &commit, &new, "Release v0.9.0", false)
+        .unwrap();
+
+    let path = tmpdir.path().join(".changelog");
+    for (release, summary) in [("v1.0.0", "The first release"), ("v0.9.0", "A beta")] {
+        let section = path.join(release).join("features");
+        std::fs::create_dir_all(&section).unwrap();
+        std::fs::write(section.join("1-something.md"), "- Something").unwrap();
+        std::fs::write(path.join(release).join("summary.md"), summary).unwrap();
+    }
+    std::fs::create_dir_all(path.join("v0.8.0")).unwrap();
+
+    let changelog = Changelog::read_from_dir(&config, &path).unwrap();
+    let releases = changelog
+        .releases
+        .iter()
+        .map(|r| (r.id.as_str(), r.maybe_date.map(|d| d.to_string())))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        releases,
+        vec![
+            ("v0.9.0", Some("2024-03-05".to_owned())),
+            ("v1.0.0", Some("2024-01-02".to_owned())),
+            ("v0.8.0", None),
+        ]
+    );
+    // The summary must be rendered in full, since it doesn't contain the date.
+    let rendered = changelog.render_keep_a_changelog_released(&config).unwrap();
+    assert!(rendered.contains("## [1.0.0] - 2024-01-02\n\nThe first release\n"));
+    assert!(rendered.contains("## [0.9.0] - 2024-03-05\n\nA beta\n"));
+}
+
+#[test]
+fn entry_authors_from_git_history() {
+    const CONFIG_FILE: &str = r#"
+[authors]
+credit_entries = true
+contributors = true
+exclude = ["maintainer"]
+handles = { "carol@example.com" = "carol" }
+"#;
+
+    init_logger();
+    let config: Config = toml::from_str(CONFIG_FILE).unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init(tmpdir.path()).unwrap();
+    let path = tmpdir.path().join(".changelog");
+    let release = path.join("v0.1.0");
+    std::fs::create_dir_all(release.join("features")).unwrap();
+    std::fs::create_dir_all(release.join("bug-fixes")).unwrap();
+    let commit = |name: &str, email: &str, files: &[(&str, &str)], removed: &[&str]| {
+        let mut index = repo.index().unwrap();
+        for (file, content) in files {
+            std::fs::write(tmpdir.path().join(file), content).unwrap();
+            index.add_path(Path::new(file)).unwrap();
+        }
+        for file in removed {
+            std::fs::remove_file(tmpdir.path().join(file)).unwrap();
+            index.remove_path(Path::new(file)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now(name, email).unwrap();
+        let maybe_parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents = maybe_parent.iter().collect::<Vec<_>>();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Commit",
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    };
+    commit(
+        "Alice",
+        "12345+alice@users.noreply.github.com",
+        &[(".changelog/v0.1.0/features/1-feature.md", "* A feature")],
+        &[],
+    );
+    commit(
+        "Carol",
+        "carol@example.com",
+        &[(".changelog/v0.1.0/bug-fixes/2-fix.md", "- A fix")],
+        &[],
+    );
+    commit(
+        "Dave",
+        "dave@example.com",
+        &[(
+            ".changelog/v0.1.0/bug-fixes/3-fix.md",
+            "- Another fix\n\n  ```\n  with some code\n  ```",
+        )],
+        &[],
+    );
+    // Renaming an entry doesn't change who gets the credit for it, and
+    // authors listed in an entry's front matter take precedence.
+    commit(
+        "Maintainer",
+        "maintainer@users.noreply.github.com",
+        &[
+            (".changelog/v0.1.0/features/1-better-name.md", "* A feature"),
+            (
+                ".changelog/v0.1.0/features/4-feature.md",
+                "+++\nauthors = [\"erin\", \"maintainer\"]\n+++\n- Another feature",
+            ),
+            (
+                ".changelog/v0.1.0/features/5-feature.md",
+                "- Yet another feature",
+            ),
+        ],
+        &[".changelog/v0.1.0/features/1-feature.md"],
+    );
+
+    let mut changelog = Changelog::read_from_dir(&config, &path).unwrap();
+    // Authors are only obtained from the Git history when asked for.
+    assert!(changelog
+        .entries()
+        .all(|e| e.entry().maybe_author.is_none()));
+    changelog.read_entry_authors(&config, &path).unwrap();
+    let release = changelog.releases.first().unwrap();
+    let authors = release
+        .changes
+        .sections
+        .iter()
+        .flat_map(|s| s.entries.iter())
+        .map(|e| {
+            (
+                e.filename.as_str(),
+                e.maybe_author.as_ref().map(|a| a.name.as_str()),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        authors,
+        vec![
+            ("2-fix.md", Some("Carol")),
+            ("3-fix.md", Some("Dave")),
+            ("1-better-name.md", Some("Alice")),
+            ("4-feature.md", Some("Maintainer")),
+            ("5-feature.md", Some("Maintainer")),
+        ]
+    );
+    assert_eq!(
+        release.render(&config).unwrap(),
+        r#"## v0.1.0
+
+### BUG FIXES
+
+- A fix (@carol)
+- Another fix (Dave)
+
+  ```
+  with some code
+  ```
+
+### FEATURES
+
+- A feature (@alice)
+- Another feature (@erin)
+- Yet another feature
+
+Contributors: @alice, @carol, @erin, Dave"#
+    );
+}